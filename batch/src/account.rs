@@ -0,0 +1,39 @@
+use chores::shared::account::AccountId;
+use ulid::Ulid;
+
+use crate::Management;
+
+pub async fn overview(service: &Management, account: Ulid) -> anyhow::Result<()> {
+    let overview = service.account_overview(AccountId::from(account)).await?;
+
+    for membership in &overview.organizations {
+        println!(
+            "{} {} {}{}",
+            membership.organization.ulid(),
+            membership.account_type.as_str(),
+            membership.name,
+            if membership.archived {
+                " (archived)"
+            } else {
+                ""
+            }
+        );
+    }
+    for task in &overview.pending_tasks {
+        let name = overview
+            .organizations
+            .iter()
+            .find(|membership| membership.organization == task.organization)
+            .map_or("", |membership| membership.name.as_str());
+        println!(
+            "{} [{}] {}",
+            task.expires
+                .map_or("no deadline".to_string(), |expires| expires
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()),
+            name,
+            task.task.ulid()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::{fs, path::Path};
+
+use chores::{
+    archive::{
+        infrastructure::PostgressArchiveRepository,
+        service::{ArchiveService, RestoreMode},
+        OrganizationArchive,
+    },
+    management::models::organization::OrganizationId,
+    shared::account::AccountId,
+};
+use ulid::Ulid;
+
+pub async fn export(database_url: &str, organization: Ulid, file: &Path) -> anyhow::Result<()> {
+    let service = ArchiveService::new(PostgressArchiveRepository::new(database_url).await?);
+    let archive = service
+        .export_organization(OrganizationId(organization))
+        .await?;
+    fs::write(file, archive.to_json()?)?;
+
+    println!(
+        "exported {} organization events, {} task events, {} catalogue tasks and {} accounts",
+        archive.organization_events.len(),
+        archive.task_events.len(),
+        archive.catalogue.len(),
+        archive.accounts.len()
+    );
+    Ok(())
+}
+
+//accepts <archived account>=<existing account>
+pub fn parse_account_mapping(value: &str) -> Result<(Ulid, Ulid), String> {
+    let (archived, existing) = value.split_once('=').ok_or(format!(
+        "account mapping {} needs the form <archived>=<existing>",
+        value
+    ))?;
+    let parse = |id: &str| Ulid::from_string(id).map_err(|_| format!("invalid account {}", id));
+    Ok((parse(archived)?, parse(existing)?))
+}
+
+pub async fn restore(
+    database_url: &str,
+    file: &Path,
+    copy: bool,
+    accounts: Vec<(Ulid, Ulid)>,
+) -> anyhow::Result<()> {
+    let service = ArchiveService::new(PostgressArchiveRepository::new(database_url).await?);
+    let archive = OrganizationArchive::from_json(&fs::read_to_string(file)?)?;
+    let mode = match copy {
+        true => RestoreMode::Copy,
+        false => RestoreMode::Original,
+    };
+    let accounts = accounts
+        .into_iter()
+        .map(|(archived, existing)| (AccountId::from(archived), AccountId::from(existing)))
+        .collect();
+    let id = service
+        .restore_organization(archive, mode, accounts)
+        .await?;
+
+    println!("restored organization {}", id.ulid());
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use chores::jobs::JobCounts;
+
+use crate::Management;
+
+pub async fn run(service: &Management) -> anyhow::Result<JobCounts> {
+    let fell_back = service.fall_back_unclaimed_tasks().await?;
+
+    println!("assigned {} unclaimed tasks", fell_back.tasks);
+    Ok(JobCounts {
+        processed: fell_back.tasks,
+        failed: fell_back.failed,
+    })
+}
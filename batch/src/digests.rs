@@ -0,0 +1,15 @@
+use chores::{jobs::JobCounts, management::application::commands::SendDigestsCommand};
+use chrono::Utc;
+
+use crate::{stats::Statistics, Management};
+
+pub async fn run(service: &Management, statistics: &Statistics) -> anyhow::Result<JobCounts> {
+    let command = SendDigestsCommand {
+        last_week: statistics.last_week(Utc::now()).await?,
+    };
+    let digests = service.send_digests(command).await?;
+    Ok(JobCounts {
+        processed: digests.sent,
+        failed: digests.failed,
+    })
+}
@@ -0,0 +1,11 @@
+use chores::jobs::JobCounts;
+
+use crate::Management;
+
+pub async fn run(service: &Management) -> anyhow::Result<JobCounts> {
+    let expired = service.expire_tasks().await?;
+    Ok(JobCounts {
+        processed: expired.tasks,
+        failed: expired.failed,
+    })
+}
@@ -0,0 +1,95 @@
+use std::future::Future;
+
+use chores::jobs::{
+    infrastructure::PostgressJobLedger,
+    run::window_start,
+    service::{JobService, JobStatus},
+    JobCounts, JobFailure,
+};
+use chrono::{Duration, Utc};
+
+pub struct Schedule {
+    pub name: &'static str,
+    //a job runs at most once per window, however often cron starts it
+    pub window: Duration,
+}
+
+pub const EXPIRY: Schedule = Schedule {
+    name: "expiry",
+    window: Duration::minutes(15),
+};
+pub const REMINDERS: Schedule = Schedule {
+    name: "reminders",
+    window: Duration::minutes(15),
+};
+pub const CLAIMS: Schedule = Schedule {
+    name: "claims",
+    window: Duration::minutes(15),
+};
+pub const REPEATS: Schedule = Schedule {
+    name: "repeats",
+    window: Duration::hours(1),
+};
+pub const DIGESTS: Schedule = Schedule {
+    name: "digests",
+    window: Duration::days(1),
+};
+pub const STATS: Schedule = Schedule {
+    name: "stats",
+    window: Duration::days(1),
+};
+pub const PURGE: Schedule = Schedule {
+    name: "purge",
+    window: Duration::days(1),
+};
+
+pub async fn scheduled<F, Fut, E>(
+    database_url: &str,
+    schedule: &Schedule,
+    run: F,
+) -> anyhow::Result<()>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<JobCounts, E>>,
+    E: Into<JobFailure>,
+{
+    let service = JobService::new(PostgressJobLedger::new(database_url).await?);
+    let window = window_start(Utc::now(), schedule.window);
+
+    match service.run_once(schedule.name, window, run).await? {
+        JobStatus::Ran(counts) => println!(
+            "{} processed {}, failed {}",
+            schedule.name, counts.processed, counts.failed
+        ),
+        JobStatus::Locked => println!("{} is already running, skipping", schedule.name),
+        JobStatus::AlreadyRan => println!(
+            "{} already ran for the window starting {}",
+            schedule.name, window
+        ),
+    }
+    Ok(())
+}
+
+pub async fn history(database_url: &str, job: Option<&str>, limit: i64) -> anyhow::Result<()> {
+    let service = JobService::new(PostgressJobLedger::new(database_url).await?);
+
+    for run in service.history(job, limit).await? {
+        println!(
+            "{:>6} {:<10} window {} started {} finished {} {:<10} processed {} failed {}{}",
+            run.id,
+            run.job,
+            run.window_start.format("%Y-%m-%d %H:%M"),
+            run.started.format("%Y-%m-%d %H:%M:%S"),
+            run.finished
+                .map(|finished| finished.format("%H:%M:%S").to_string())
+                .unwrap_or("-".to_string()),
+            run.outcome.as_str(),
+            run.counts.processed,
+            run.counts.failed,
+            run.message
+                .map(|message| format!(" ({})", message))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
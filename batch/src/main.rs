@@ -1,3 +1,158 @@
-fn main() {
-    println!("Hello, world!");
+use std::path::PathBuf;
+
+use chores::{
+    management::infrastructure::{PostgressOrganizationRepository, PostgressTaskRepository},
+    ManagementService,
+};
+use chrono::Duration;
+use clap::{Parser, Subcommand};
+use notifier::StdoutNotifier;
+use ulid::Ulid;
+
+mod account;
+mod archive;
+mod claims;
+mod digests;
+mod expiry;
+mod jobs;
+mod notifier;
+mod organization;
+mod purge;
+mod reminders;
+mod repeats;
+mod stats;
+
+type Management =
+    ManagementService<PostgressTaskRepository, PostgressOrganizationRepository, StdoutNotifier>;
+
+async fn management(database_url: &str) -> anyhow::Result<Management> {
+    Ok(ManagementService::new(
+        PostgressTaskRepository::new(database_url).await?,
+        PostgressOrganizationRepository::new(database_url).await?,
+        StdoutNotifier,
+    ))
+}
+
+#[derive(Parser)]
+#[command(about = "daily operations for jira-for-chores")]
+struct Cli {
+    #[arg(long, env = "DATABASE_URL")]
+    database_url: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write an organization's event streams, catalogue and accounts to an archive file
+    Export { organization: Ulid, file: PathBuf },
+    /// Replay an archive file into the database
+    Restore {
+        file: PathBuf,
+        /// Give the organization new ids so it can sit next to the original
+        #[arg(long)]
+        copy: bool,
+        /// Use an existing account for an archived one missing from the database, as <archived>=<existing>
+        #[arg(long = "account", value_parser = archive::parse_account_mapping)]
+        accounts: Vec<(Ulid, Ulid)>,
+    },
+    /// Print every event of an organization, role changes included, oldest first
+    Audit { organization: Ulid },
+    /// List the organizations an account belongs to and its pending tasks across all of them
+    Overview { account: Ulid },
+    /// Rebuild the organization member and invitation read models from the event store
+    RebuildProjections,
+    /// Expire pending and open tasks past their deadline and reassign pending ones where the organization asks for it
+    Expire,
+    /// Remind workers of pending tasks that are about to expire
+    Reminders {
+        /// How long before expiry a reminder goes out, repeat for several reminders
+        #[arg(long = "lead", value_parser = reminders::parse_lead, default_values = ["24h", "1h"])]
+        leads: Vec<Duration>,
+    },
+    /// Hand out the repeating tasks that are due
+    Repeats,
+    /// Assign pooled tasks nobody claimed before their claim deadline
+    Claims,
+    /// Send every worker a summary of their pending tasks and last week's numbers
+    Digests,
+    /// Aggregate weekly task counts per organization and account
+    Stats,
+    /// Delete everything of organizations that have been archived for longer than the grace period
+    Purge {
+        #[arg(long, value_parser = reminders::parse_lead, default_value = "30d")]
+        grace: Duration,
+    },
+    /// Show the most recent runs of the scheduled jobs
+    History {
+        /// Only show runs of this job
+        job: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Export { organization, file } => {
+            archive::export(&cli.database_url, organization, &file).await
+        }
+        Command::Restore {
+            file,
+            copy,
+            accounts,
+        } => archive::restore(&cli.database_url, &file, copy, accounts).await,
+        Command::Audit { organization } => {
+            organization::audit(&cli.database_url, organization).await
+        }
+        Command::Overview { account } => {
+            let service = management(&cli.database_url).await?;
+            account::overview(&service, account).await
+        }
+        Command::RebuildProjections => organization::rebuild_projections(&cli.database_url).await,
+        Command::Expire => {
+            let service = management(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::EXPIRY, || expiry::run(&service)).await
+        }
+        Command::Reminders { leads } => {
+            let service = management(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::REMINDERS, || {
+                reminders::run(&service, leads)
+            })
+            .await
+        }
+        Command::Repeats => {
+            let service = management(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::REPEATS, || repeats::run(&service)).await
+        }
+        Command::Claims => {
+            let service = management(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::CLAIMS, || claims::run(&service)).await
+        }
+        Command::Digests => {
+            let service = management(&cli.database_url).await?;
+            let statistics = stats::statistics(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::DIGESTS, || {
+                digests::run(&service, &statistics)
+            })
+            .await
+        }
+        Command::Stats => {
+            let service = stats::statistics(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::STATS, || stats::run(&service)).await
+        }
+        Command::Purge { grace } => {
+            let service = purge::archives(&cli.database_url).await?;
+            jobs::scheduled(&cli.database_url, &jobs::PURGE, || {
+                purge::run(&service, grace)
+            })
+            .await
+        }
+        Command::History { job, limit } => {
+            jobs::history(&cli.database_url, job.as_deref(), limit).await
+        }
+    }
 }
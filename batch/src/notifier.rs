@@ -0,0 +1,18 @@
+use chores::{
+    management::application::notifications::Notification, shared::account::AccountId, Notifier,
+};
+
+//delivery channels are not wired up yet, notifications end up in the job output
+#[derive(Debug, Clone)]
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    async fn notify(
+        &self,
+        account: AccountId,
+        notification: Notification,
+    ) -> Result<(), anyhow::Error> {
+        println!("notify {}: {}", account.ulid(), notification);
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use chores::{
+    management::{
+        infrastructure::PostgressOrganizationRepository, models::organization::OrganizationId,
+    },
+    OrganizationRepository,
+};
+use ulid::Ulid;
+
+pub async fn audit(database_url: &str, organization: Ulid) -> anyhow::Result<()> {
+    let repo = PostgressOrganizationRepository::new(database_url).await?;
+
+    for entry in repo.query_for_history(OrganizationId(organization)).await? {
+        println!(
+            "{} {}",
+            entry.created.format("%Y-%m-%d %H:%M:%S"),
+            serde_json::to_string(&entry.event)?
+        );
+    }
+    Ok(())
+}
+
+pub async fn rebuild_projections(database_url: &str) -> anyhow::Result<()> {
+    let repo = PostgressOrganizationRepository::new(database_url).await?;
+
+    println!(
+        "rebuilt members and invitations of {} organizations",
+        repo.rebuild_projections().await?
+    );
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use chores::{
+    archive::{infrastructure::PostgressArchiveRepository, service::ArchiveService},
+    jobs::JobCounts,
+};
+use chrono::{Duration, Utc};
+
+pub type Archives = ArchiveService<PostgressArchiveRepository>;
+
+pub async fn archives(database_url: &str) -> anyhow::Result<Archives> {
+    Ok(ArchiveService::new(
+        PostgressArchiveRepository::new(database_url).await?,
+    ))
+}
+
+pub async fn run(service: &Archives, grace: Duration) -> anyhow::Result<JobCounts> {
+    let purged = service.purge_archived(Utc::now(), grace).await?;
+    Ok(JobCounts {
+        processed: purged.organizations,
+        failed: purged.failed,
+    })
+}
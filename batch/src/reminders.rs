@@ -0,0 +1,28 @@
+use chores::{jobs::JobCounts, management::application::commands::SendRemindersCommand};
+use chrono::Duration;
+
+use crate::Management;
+
+pub async fn run(service: &Management, leads: Vec<Duration>) -> anyhow::Result<JobCounts> {
+    let reminders = service
+        .send_reminders(SendRemindersCommand { thresholds: leads })
+        .await?;
+    Ok(JobCounts {
+        processed: reminders.sent,
+        failed: reminders.failed,
+    })
+}
+
+//accepts lead times like 90m, 24h or 2d
+pub fn parse_lead(value: &str) -> Result<Duration, String> {
+    let (amount, unit) = value.split_at(value.len().saturating_sub(1));
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("invalid lead time {}", value))?;
+    match unit {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        _ => Err(format!("lead time {} needs a m, h or d suffix", value)),
+    }
+}
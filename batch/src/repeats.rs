@@ -0,0 +1,16 @@
+use chores::jobs::JobCounts;
+
+use crate::Management;
+
+pub async fn run(service: &Management) -> anyhow::Result<JobCounts> {
+    let assigned = service.assign_repeating_tasks().await?;
+
+    println!(
+        "assigned {} tasks from {} repeating tasks",
+        assigned.tasks, assigned.repeats
+    );
+    Ok(JobCounts {
+        processed: assigned.repeats,
+        failed: assigned.failed,
+    })
+}
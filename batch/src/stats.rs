@@ -0,0 +1,23 @@
+use chores::{
+    jobs::{JobCounts, JobFailure},
+    management::{
+        application::statistics::StatisticsService, infrastructure::PostgressStatisticsRepository,
+    },
+};
+use chrono::Utc;
+
+pub type Statistics = StatisticsService<PostgressStatisticsRepository>;
+
+pub async fn statistics(database_url: &str) -> anyhow::Result<Statistics> {
+    Ok(StatisticsService::new(
+        PostgressStatisticsRepository::new(database_url).await?,
+    ))
+}
+
+//the first run backfills every week since the first task event
+pub async fn run(service: &Statistics) -> Result<JobCounts, JobFailure> {
+    Ok(JobCounts {
+        processed: service.compute_weekly_stats(Utc::now()).await?,
+        failed: 0,
+    })
+}
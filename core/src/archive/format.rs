@@ -0,0 +1,765 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    catalogue::{task::CatalogueTask, CatalogueTaskId},
+    management::models::{
+        availability::UnavailabilityId,
+        events::{OrganizationEvent, TaskEvent},
+        organization::{
+            AssignmentType, OrganizationId, RepeatingTaskId, TagId, TaskAssignmentType,
+        },
+        rotation::RotationKey,
+        task::TaskId,
+        trade::TradeId,
+        workload::CapTarget,
+    },
+    shared::account::AccountId,
+};
+
+//bump whenever the layout below or the serialized events change incompatibly
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationArchive {
+    pub version: u32,
+    pub organization: OrganizationId,
+    pub exported: DateTime<Utc>,
+    pub organization_events: Vec<ArchivedOrganizationEvent>,
+    pub task_events: Vec<ArchivedTaskEvent>,
+    pub catalogue: Vec<CatalogueTask>,
+    pub accounts: Vec<ArchivedAccount>,
+}
+
+//accounts are matched against the target database on restore, credentials never leave it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAccount {
+    pub id: AccountId,
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedOrganizationEvent {
+    pub created: DateTime<Utc>,
+    pub event: OrganizationEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedTaskEvent {
+    pub task: TaskId,
+    pub created: DateTime<Utc>,
+    pub event: TaskEvent,
+}
+
+impl OrganizationArchive {
+    pub fn to_json(&self) -> Result<String, ArchiveError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<OrganizationArchive, ArchiveError> {
+        let archive: OrganizationArchive = serde_json::from_str(json)?;
+        archive.check_version()?;
+        Ok(archive)
+    }
+
+    pub fn check_version(&self) -> Result<(), ArchiveError> {
+        if self.version != ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(self.version));
+        }
+        Ok(())
+    }
+
+    pub fn referenced_accounts(&self) -> Vec<AccountId> {
+        let mut accounts: Vec<AccountId> = self
+            .organization_events
+            .iter()
+            .filter_map(|archived| match &archived.event {
+                OrganizationEvent::AccountLinked { account, .. }
+                | OrganizationEvent::EditorAddedToTag { account, .. }
+                | OrganizationEvent::WorkerAddedToTag { account, .. } => Some(*account),
+                //tasks can be set up to go to one account
+                OrganizationEvent::SettingsChanged { settings, .. } => {
+                    to_account(&settings.default_assignment_type)
+                }
+                OrganizationEvent::ReassignmentPolicySet {
+                    policy: Some(policy),
+                } => to_account(&policy.assignment_type),
+                OrganizationEvent::RepeatingTaskAdded { assigned_to, .. } => match assigned_to {
+                    AssignmentType::Account(account) => Some(*account),
+                    AssignmentType::Tags {
+                        assignment_type, ..
+                    } => to_account(assignment_type),
+                },
+                _ => None,
+            })
+            .chain(self.catalogue.iter().map(|task| task.created_by))
+            .chain(self.task_events.iter().flat_map(|archived| {
+                match &archived.event {
+                    TaskEvent::Assigned {
+                        assigned_to,
+                        assigned_by,
+                        ..
+                    } => vec![*assigned_to, *assigned_by],
+                    TaskEvent::Posted {
+                        posted_by,
+                        claim_deadline,
+                        ..
+                    } => [Some(*posted_by)]
+                        .into_iter()
+                        .chain(
+                            claim_deadline
+                                .as_ref()
+                                .map(|deadline| to_account(&deadline.fallback)),
+                        )
+                        .flatten()
+                        .collect(),
+                    TaskEvent::Claimed { account, .. } => vec![*account],
+                    TaskEvent::HandedOver { to, .. } => vec![*to],
+                    _ => Vec::new(),
+                }
+            }))
+            .collect();
+        accounts.sort();
+        accounts.dedup();
+        accounts
+    }
+
+    //ids owned by the organization, accounts are shared between organizations and are kept
+    fn owned_ids(&self) -> Vec<Ulid> {
+        let mut ids = vec![self.organization.ulid()];
+        ids.extend(self.catalogue.iter().map(|task| task.id.ulid()));
+        ids.extend(self.task_events.iter().map(|event| event.task.ulid()));
+        ids.extend(
+            self.organization_events
+                .iter()
+                .filter_map(|archived| match &archived.event {
+                    OrganizationEvent::TagAdded { tag_id, .. } => Some(tag_id.ulid()),
+                    OrganizationEvent::RepeatingTaskAdded { id, .. } => Some(id.ulid()),
+                    OrganizationEvent::UnavailabilityAdded { id, .. } => Some(id.0),
+                    OrganizationEvent::TradeProposed { id, .. } => Some(id.0),
+                    _ => None,
+                }),
+        );
+        ids
+    }
+
+    //gives every organization owned id a fresh value so the archive can be restored next to the original
+    pub fn with_new_ids(mut self) -> OrganizationArchive {
+        let ids = IdMap {
+            owned: self
+                .owned_ids()
+                .into_iter()
+                .map(|id| (id, Ulid::new()))
+                .collect(),
+            accounts: HashMap::new(),
+        };
+        //invitation codes are unique across organizations, a copy starts without invitations
+        self.organization_events.retain(|archived| {
+            !matches!(
+                archived.event,
+                OrganizationEvent::InvitationCreated { .. }
+                    | OrganizationEvent::InvitationRedeemed { .. }
+                    | OrganizationEvent::InvitationRevoked { .. }
+            )
+        });
+        self.remap(&ids);
+        self
+    }
+
+    //points archived accounts at existing accounts of the target database
+    pub fn with_accounts(mut self, accounts: HashMap<AccountId, AccountId>) -> OrganizationArchive {
+        self.remap(&IdMap {
+            owned: HashMap::new(),
+            accounts,
+        });
+        self
+    }
+
+    fn remap(&mut self, ids: &IdMap) {
+        self.organization = ids.organization(self.organization);
+        for archived in &mut self.organization_events {
+            ids.organization_event(&mut archived.event);
+        }
+        for archived in &mut self.task_events {
+            archived.task = ids.task(archived.task);
+            ids.task_event(&mut archived.event);
+        }
+        for task in &mut self.catalogue {
+            task.id = ids.catalogue_task(task.id);
+            task.organization = ids.organization(task.organization);
+            task.created_by = ids.account(task.created_by);
+        }
+        for account in &mut self.accounts {
+            account.id = ids.account(account.id);
+        }
+    }
+}
+
+fn to_account(assignment_type: &TaskAssignmentType) -> Option<AccountId> {
+    match assignment_type {
+        TaskAssignmentType::ToAccount { account } => Some(*account),
+        _ => None,
+    }
+}
+
+//replacements for organization owned ids and for accounts, anything not listed keeps its id
+struct IdMap {
+    owned: HashMap<Ulid, Ulid>,
+    accounts: HashMap<AccountId, AccountId>,
+}
+
+impl IdMap {
+    fn ulid(&self, id: Ulid) -> Ulid {
+        self.owned.get(&id).copied().unwrap_or(id)
+    }
+
+    fn organization(&self, id: OrganizationId) -> OrganizationId {
+        OrganizationId(self.ulid(id.0))
+    }
+
+    fn tag(&self, id: TagId) -> TagId {
+        TagId(self.ulid(id.0))
+    }
+
+    fn tags<T: FromIterator<TagId>>(&self, tags: &HashSet<TagId>) -> T {
+        tags.iter().map(|tag| self.tag(*tag)).collect()
+    }
+
+    fn task(&self, id: TaskId) -> TaskId {
+        TaskId(self.ulid(id.0))
+    }
+
+    fn catalogue_task(&self, id: CatalogueTaskId) -> CatalogueTaskId {
+        CatalogueTaskId::from(self.ulid(id.ulid()))
+    }
+
+    fn account(&self, id: AccountId) -> AccountId {
+        self.accounts.get(&id).copied().unwrap_or(id)
+    }
+
+    fn trade(&self, id: TradeId) -> TradeId {
+        TradeId(self.ulid(id.0))
+    }
+
+    fn assignment_type(&self, assignment: &mut AssignmentType) {
+        match assignment {
+            AssignmentType::Account(account) => *account = self.account(*account),
+            AssignmentType::Tags {
+                tags,
+                assignment_type,
+            } => {
+                *tags = self.tags(tags);
+                self.task_assignment_type(assignment_type);
+            }
+        }
+    }
+
+    fn task_assignment_type(&self, assignment_type: &mut TaskAssignmentType) {
+        if let TaskAssignmentType::ToAccount { account } = assignment_type {
+            *account = self.account(*account);
+        }
+    }
+
+    //every variant is listed so new events cannot slip through with stale ids
+    fn task_event(&self, event: &mut TaskEvent) {
+        match event {
+            TaskEvent::Assigned {
+                id,
+                organization,
+                assigned_to,
+                assigned_by,
+                task,
+                expires: _,
+                tags,
+                reassigned_from,
+                reassignments: _,
+                seed: _,
+                candidates,
+            } => {
+                *id = self.task(*id);
+                *organization = self.organization(*organization);
+                *assigned_to = self.account(*assigned_to);
+                *assigned_by = self.account(*assigned_by);
+                *task = self.catalogue_task(*task);
+                *tags = self.tags(tags);
+                *reassigned_from = reassigned_from.map(|task| self.task(task));
+                *candidates = candidates
+                    .iter()
+                    .map(|account| self.account(*account))
+                    .collect();
+            }
+            TaskEvent::Posted {
+                id,
+                organization,
+                posted_by,
+                task,
+                expires: _,
+                tags,
+                claim_deadline,
+            } => {
+                *id = self.task(*id);
+                *organization = self.organization(*organization);
+                *posted_by = self.account(*posted_by);
+                *task = self.catalogue_task(*task);
+                *tags = self.tags(tags);
+                if let Some(deadline) = claim_deadline {
+                    self.task_assignment_type(&mut deadline.fallback);
+                }
+            }
+            TaskEvent::Claimed { task_id, account } | TaskEvent::Unclaimed { task_id, account } => {
+                *task_id = self.task(*task_id);
+                *account = self.account(*account);
+            }
+            TaskEvent::HandedOver {
+                task_id,
+                from,
+                to,
+                trade,
+            } => {
+                *task_id = self.task(*task_id);
+                *from = self.account(*from);
+                *to = self.account(*to);
+                *trade = self.trade(*trade);
+            }
+            TaskEvent::Rejected {
+                task_id,
+                assigned_by,
+            }
+            | TaskEvent::Expired {
+                task_id,
+                assigned_by,
+            }
+            | TaskEvent::Cancelled {
+                task_id,
+                cancelled_by: assigned_by,
+            } => {
+                *task_id = self.task(*task_id);
+                *assigned_by = self.account(*assigned_by);
+            }
+            TaskEvent::ClaimDeadlinePassed { task_id }
+            | TaskEvent::Finished { task_id }
+            | TaskEvent::TimeAdded { task_id, .. } => *task_id = self.task(*task_id),
+        }
+    }
+
+    fn organization_event(&self, event: &mut OrganizationEvent) {
+        match event {
+            OrganizationEvent::Created { id, name: _ } => *id = self.organization(*id),
+            OrganizationEvent::TagAdded {
+                organization_id,
+                tag_id,
+                name: _,
+            } => {
+                *organization_id = self.organization(*organization_id);
+                *tag_id = self.tag(*tag_id);
+            }
+            OrganizationEvent::EditorAddedToTag { tag_id, account }
+            | OrganizationEvent::WorkerAddedToTag { tag_id, account }
+            | OrganizationEvent::EditorRemovedFromTag { tag_id, account }
+            | OrganizationEvent::WorkerRemovedFromTag { tag_id, account } => {
+                *tag_id = self.tag(*tag_id);
+                *account = self.account(*account);
+            }
+            OrganizationEvent::TagRenamed { tag_id, name: _ } => *tag_id = self.tag(*tag_id),
+            OrganizationEvent::TagParentSet { tag_id, parent } => {
+                *tag_id = self.tag(*tag_id);
+                *parent = parent.map(|parent| self.tag(parent));
+            }
+            OrganizationEvent::TagRemoverd { tag } => *tag = self.tag(*tag),
+            OrganizationEvent::AccountLinked {
+                account,
+                account_type: _,
+            }
+            | OrganizationEvent::OwnershipDeclined { account }
+            | OrganizationEvent::AccountUnlinked { account }
+            | OrganizationEvent::Archived {
+                archived_by: account,
+                ..
+            }
+            | OrganizationEvent::Unarchived {
+                unarchived_by: account,
+            } => *account = self.account(*account),
+            OrganizationEvent::ReassignmentPolicySet { policy } => {
+                if let Some(policy) = policy {
+                    self.task_assignment_type(&mut policy.assignment_type);
+                }
+            }
+            OrganizationEvent::SettingsChanged {
+                settings,
+                changed_by,
+            } => {
+                self.task_assignment_type(&mut settings.default_assignment_type);
+                *changed_by = self.account(*changed_by);
+            }
+            OrganizationEvent::InvitationCreated {
+                tags, created_by, ..
+            } => {
+                *tags = self.tags(tags);
+                *created_by = self.account(*created_by);
+            }
+            OrganizationEvent::InvitationRedeemed { id: _, account }
+            | OrganizationEvent::InvitationRevoked {
+                id: _,
+                revoked_by: account,
+            } => *account = self.account(*account),
+            OrganizationEvent::RoleCapabilitiesSet { .. } => {}
+            OrganizationEvent::RoleChanged {
+                account,
+                changed_by,
+                ..
+            } => {
+                *account = self.account(*account);
+                *changed_by = self.account(*changed_by);
+            }
+            OrganizationEvent::UnavailabilityAdded {
+                id,
+                account,
+                unavailability: _,
+                added_by,
+            } => {
+                *id = UnavailabilityId(self.ulid(id.0));
+                *account = self.account(*account);
+                *added_by = self.account(*added_by);
+            }
+            OrganizationEvent::UnavailabilityRemoved { id, removed_by } => {
+                *id = UnavailabilityId(self.ulid(id.0));
+                *removed_by = self.account(*removed_by);
+            }
+            OrganizationEvent::TradeProposed {
+                id,
+                proposed_by,
+                counterparty,
+                offered,
+                requested,
+                approvers,
+            } => {
+                *id = self.trade(*id);
+                *proposed_by = self.account(*proposed_by);
+                *counterparty = self.account(*counterparty);
+                *offered = self.task(*offered);
+                *requested = requested.map(|task| self.task(task));
+                *approvers = approvers
+                    .iter()
+                    .map(|account| self.account(*account))
+                    .collect();
+            }
+            OrganizationEvent::TradeAccepted { id } | OrganizationEvent::TradeCompleted { id } => {
+                *id = self.trade(*id)
+            }
+            OrganizationEvent::TradeApproved {
+                id,
+                approved_by: account,
+            }
+            | OrganizationEvent::TradeDeclined {
+                id,
+                declined_by: account,
+            } => {
+                *id = self.trade(*id);
+                *account = self.account(*account);
+            }
+            OrganizationEvent::WorkloadCapsSet {
+                target,
+                caps: _,
+                set_by,
+            } => {
+                *target = match target {
+                    CapTarget::Account(account) => CapTarget::Account(self.account(*account)),
+                    CapTarget::Tag(tag) => CapTarget::Tag(self.tag(*tag)),
+                };
+                *set_by = self.account(*set_by);
+            }
+            OrganizationEvent::HandicapSet {
+                account, set_by, ..
+            }
+            | OrganizationEvent::ProfileSet {
+                account, set_by, ..
+            } => {
+                *account = self.account(*account);
+                *set_by = self.account(*set_by);
+            }
+            OrganizationEvent::RepeatingTaskAdded {
+                id,
+                requesting_account,
+                assigned_to,
+                tasks,
+                ..
+            } => {
+                *id = RepeatingTaskId(self.ulid(id.0));
+                *requesting_account = self.account(*requesting_account);
+                self.assignment_type(assigned_to);
+                *tasks = tasks
+                    .iter()
+                    .map(|task| self.catalogue_task(*task))
+                    .collect();
+            }
+            OrganizationEvent::RepeatingTaskAssigned { id, .. }
+            | OrganizationEvent::RepeatingTaskRemoved { id } => {
+                *id = RepeatingTaskId(self.ulid(id.0))
+            }
+            OrganizationEvent::RotationAdvanced { key, last } => {
+                *key = match key {
+                    RotationKey::Tags(tags) => {
+                        RotationKey::Tags(tags.iter().map(|tag| self.tag(*tag)).collect())
+                    }
+                    RotationKey::Task(task) => RotationKey::Task(self.catalogue_task(*task)),
+                };
+                *last = self.account(*last);
+            }
+            OrganizationEvent::OwnershipOffered { from, to }
+            | OrganizationEvent::OwnershipTransferred { from, to } => {
+                *from = self.account(*from);
+                *to = self.account(*to);
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("archive version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("organization already exists")]
+    OrganizationExists,
+    #[error("organization does not exist")]
+    OrganizationDoesNotExist,
+    #[error("accounts do not exist: {}", .0.join(", "))]
+    AccountsDoNotExist(Vec<String>),
+    #[error("invalid archive")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::management::models::{
+        invitation::InvitationId,
+        organization::{AccountType, ReassignmentPolicy},
+        settings::OrganizationSettings,
+        task::ClaimDeadline,
+    };
+
+    use super::*;
+
+    fn to_one(account: AccountId) -> TaskAssignmentType {
+        TaskAssignmentType::ToAccount { account }
+    }
+
+    fn organization_event(event: OrganizationEvent) -> ArchivedOrganizationEvent {
+        ArchivedOrganizationEvent {
+            created: Utc::now(),
+            event,
+        }
+    }
+
+    //an organization whose settings, reassignment policy, repeats and pooled tasks all point at one account
+    fn archive(owner: AccountId, worker: AccountId) -> OrganizationArchive {
+        let organization = OrganizationId(Ulid::new());
+        let tag = TagId(Ulid::new());
+        let task = TaskId(Ulid::new());
+        let catalogue = CatalogueTaskId::new();
+        OrganizationArchive {
+            version: ARCHIVE_VERSION,
+            organization,
+            exported: Utc::now(),
+            organization_events: vec![
+                organization_event(OrganizationEvent::Created {
+                    id: organization,
+                    name: "home".to_string(),
+                }),
+                organization_event(OrganizationEvent::AccountLinked {
+                    account: owner,
+                    account_type: AccountType::Owner,
+                }),
+                organization_event(OrganizationEvent::AccountLinked {
+                    account: worker,
+                    account_type: AccountType::Worker,
+                }),
+                organization_event(OrganizationEvent::TagAdded {
+                    organization_id: organization,
+                    tag_id: tag,
+                    name: "chores".to_string(),
+                }),
+                organization_event(OrganizationEvent::WorkerAddedToTag {
+                    tag_id: tag,
+                    account: worker,
+                }),
+                organization_event(OrganizationEvent::SettingsChanged {
+                    settings: OrganizationSettings {
+                        default_assignment_type: to_one(worker),
+                        ..Default::default()
+                    },
+                    changed_by: owner,
+                }),
+                organization_event(OrganizationEvent::ReassignmentPolicySet {
+                    policy: Some(ReassignmentPolicy {
+                        assignment_type: to_one(worker),
+                        max_reassignments: 1,
+                        expires_after: None,
+                    }),
+                }),
+                organization_event(OrganizationEvent::RepeatingTaskAdded {
+                    id: RepeatingTaskId(Ulid::new()),
+                    requesting_account: owner,
+                    assigned_to: AssignmentType::Tags {
+                        tags: HashSet::from([tag]),
+                        assignment_type: to_one(worker),
+                    },
+                    tasks: vec![catalogue],
+                    period_days: 7,
+                    starts: Utc::now(),
+                }),
+                organization_event(OrganizationEvent::RepeatingTaskAdded {
+                    id: RepeatingTaskId(Ulid::new()),
+                    requesting_account: owner,
+                    assigned_to: AssignmentType::Account(worker),
+                    tasks: vec![catalogue],
+                    period_days: 1,
+                    starts: Utc::now(),
+                }),
+                organization_event(OrganizationEvent::InvitationCreated {
+                    id: InvitationId(Ulid::new()),
+                    code: "CODE".to_string(),
+                    account_type: AccountType::Worker,
+                    tags: HashSet::from([tag]),
+                    max_uses: None,
+                    expires: None,
+                    created_by: owner,
+                }),
+            ],
+            task_events: vec![ArchivedTaskEvent {
+                task,
+                created: Utc::now(),
+                event: TaskEvent::Posted {
+                    id: task,
+                    organization,
+                    posted_by: owner,
+                    task: catalogue,
+                    expires: None,
+                    tags: HashSet::from([tag]),
+                    claim_deadline: Some(ClaimDeadline {
+                        at: Utc::now(),
+                        fallback: to_one(worker),
+                    }),
+                },
+            }],
+            catalogue: Vec::new(),
+            accounts: vec![
+                ArchivedAccount {
+                    id: owner,
+                    username: "owner".to_string(),
+                },
+                ArchivedAccount {
+                    id: worker,
+                    username: "worker".to_string(),
+                },
+            ],
+        }
+    }
+
+    //every account a task can be set up to go to
+    fn assigned_accounts(archive: &OrganizationArchive) -> Vec<AccountId> {
+        let mut accounts: Vec<AccountId> = archive
+            .organization_events
+            .iter()
+            .filter_map(|archived| match &archived.event {
+                OrganizationEvent::SettingsChanged { settings, .. } => {
+                    to_account(&settings.default_assignment_type)
+                }
+                OrganizationEvent::ReassignmentPolicySet { policy } => {
+                    to_account(&policy.as_ref()?.assignment_type)
+                }
+                OrganizationEvent::RepeatingTaskAdded { assigned_to, .. } => match assigned_to {
+                    AssignmentType::Account(account) => Some(*account),
+                    AssignmentType::Tags {
+                        assignment_type, ..
+                    } => to_account(assignment_type),
+                },
+                _ => None,
+            })
+            .collect();
+        accounts.extend(
+            archive
+                .task_events
+                .iter()
+                .filter_map(|archived| match &archived.event {
+                    TaskEvent::Posted { claim_deadline, .. } => {
+                        to_account(&claim_deadline.as_ref()?.fallback)
+                    }
+                    _ => None,
+                }),
+        );
+        accounts
+    }
+
+    fn round_trip(archive: &OrganizationArchive) -> OrganizationArchive {
+        OrganizationArchive::from_json(&archive.to_json().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn accounts_are_remapped_wherever_tasks_go_to_them() {
+        let [owner, worker, existing] = [AccountId::new(), AccountId::new(), AccountId::new()];
+        let archive = round_trip(&archive(owner, worker));
+        assert_eq!(assigned_accounts(&archive), vec![worker; 5]);
+        assert!(archive.referenced_accounts().contains(&worker));
+
+        let restored = round_trip(&archive.with_accounts(HashMap::from([(worker, existing)])));
+        assert_eq!(assigned_accounts(&restored), vec![existing; 5]);
+        let referenced = restored.referenced_accounts();
+        assert!(referenced.contains(&existing));
+        assert!(!referenced.contains(&worker));
+        assert!(referenced.contains(&owner));
+    }
+
+    #[test]
+    fn a_copy_gets_new_ids_and_keeps_its_accounts() {
+        let [owner, worker] = [AccountId::new(), AccountId::new()];
+        let original = archive(owner, worker);
+        let copy = round_trip(&original.clone().with_new_ids());
+
+        assert_ne!(copy.organization, original.organization);
+        assert_eq!(assigned_accounts(&copy), vec![worker; 5]);
+        assert_eq!(copy.referenced_accounts(), original.referenced_accounts());
+        assert!(!copy
+            .organization_events
+            .iter()
+            .any(|archived| matches!(archived.event, OrganizationEvent::InvitationCreated { .. })));
+
+        let tags: Vec<TagId> = copy
+            .organization_events
+            .iter()
+            .filter_map(|archived| match &archived.event {
+                OrganizationEvent::TagAdded {
+                    organization_id,
+                    tag_id,
+                    ..
+                } => {
+                    assert_eq!(*organization_id, copy.organization);
+                    Some(*tag_id)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(tags.len(), 1);
+        let ArchivedTaskEvent {
+            task,
+            event:
+                TaskEvent::Posted {
+                    id,
+                    organization,
+                    tags: posted_to,
+                    ..
+                },
+            ..
+        } = &copy.task_events[0]
+        else {
+            panic!("expected a posted task");
+        };
+        assert_eq!(id, task);
+        assert_ne!(*id, original.task_events[0].task);
+        assert_eq!(*organization, copy.organization);
+        assert_eq!(*posted_to, HashSet::from([tags[0]]));
+    }
+}
@@ -0,0 +1,318 @@
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgPoolOptions, types::Json, types::Uuid};
+
+use crate::{
+    catalogue::{task::CatalogueTask, Requirements, DEFAULT_EFFORT},
+    management::{
+        infrastructure::{append_task_event, project_organization},
+        models::{
+            events::{OrganizationEvent, TaskEvent},
+            organization::OrganizationId,
+        },
+    },
+    shared::account::AccountId,
+};
+
+use super::{
+    format::{
+        ArchiveError, ArchivedAccount, ArchivedOrganizationEvent, ArchivedTaskEvent,
+        ARCHIVE_VERSION,
+    },
+    service::ArchiveRepository,
+    OrganizationArchive,
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgressArchiveRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgressArchiveRepository {
+    pub async fn new(path: &str) -> anyhow::Result<PostgressArchiveRepository> {
+        let pool = PgPoolOptions::new()
+            .test_before_acquire(false)
+            .connect(path)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+impl ArchiveRepository for PostgressArchiveRepository {
+    async fn export(&self, id: &OrganizationId) -> Result<OrganizationArchive, anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+        //every read below has to see the same snapshot
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let organization_events: Vec<ArchivedOrganizationEvent> = sqlx::query!(
+            r#"SELECT payload as "payload: Json<OrganizationEvent>", created
+            FROM ORGANIZATION_EVENT
+            WHERE organization = $1
+            ORDER BY sequence"#,
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|record| ArchivedOrganizationEvent {
+            created: record.created,
+            event: record.payload.0,
+        })
+        .collect();
+
+        if organization_events.is_empty() {
+            return Err(ArchiveError::OrganizationDoesNotExist.into());
+        }
+
+        let task_events = sqlx::query!(
+            r#"SELECT task, payload as "payload: Json<TaskEvent>", created
+            FROM TASK_EVENT
+            WHERE organization = $1
+            ORDER BY sequence"#,
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|record| ArchivedTaskEvent {
+            task: record.task.into(),
+            created: record.created,
+            event: record.payload.0,
+        })
+        .collect();
+
+        let catalogue = sqlx::query!(
+            "SELECT id, organization, created_by, title, description, effort, min_age, skills
+            FROM CATALOGUE_TASK
+            WHERE organization = $1",
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|record| CatalogueTask {
+            id: record.id.into(),
+            organization: record.organization.into(),
+            created_by: record.created_by.into(),
+            title: record.title,
+            description: record.description,
+            effort: record.effort.try_into().unwrap_or(DEFAULT_EFFORT),
+            requirements: Requirements::new(
+                record.min_age.and_then(|age| age.try_into().ok()),
+                record.skills,
+            ),
+        })
+        .collect();
+
+        let mut archive = OrganizationArchive {
+            version: ARCHIVE_VERSION,
+            organization: *id,
+            exported: Utc::now(),
+            organization_events,
+            task_events,
+            catalogue,
+            accounts: Vec::new(),
+        };
+
+        let referenced: Vec<Uuid> = archive
+            .referenced_accounts()
+            .iter()
+            .map(|account| Uuid::from(account.ulid()))
+            .collect();
+        archive.accounts = sqlx::query!(
+            "SELECT id, username
+            FROM ACCOUNT
+            WHERE id = ANY($1)",
+            &referenced
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|record| ArchivedAccount {
+            id: record.id.into(),
+            username: record.username,
+        })
+        .collect();
+
+        tx.commit().await?;
+        Ok(archive)
+    }
+
+    async fn restore(&self, archive: &OrganizationArchive) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM ORGANIZATION_EVENT WHERE organization = $1) as "exists!""#,
+            Uuid::from(archive.organization.ulid())
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if exists {
+            return Err(ArchiveError::OrganizationExists.into());
+        }
+
+        //archives carry no credentials, every account has to exist in the target database already
+        let referenced = archive.referenced_accounts();
+        let ids: Vec<Uuid> = referenced
+            .iter()
+            .map(|account| Uuid::from(account.ulid()))
+            .collect();
+        let existing: Vec<AccountId> =
+            sqlx::query_scalar!("SELECT id FROM ACCOUNT WHERE id = ANY($1)", &ids)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(AccountId::from)
+                .collect();
+        let missing: Vec<String> = referenced
+            .iter()
+            .filter(|account| !existing.contains(account))
+            .map(
+                |account| match archive.accounts.iter().find(|known| known.id == *account) {
+                    Some(known) => known.username.clone(),
+                    None => account.ulid().to_string(),
+                },
+            )
+            .collect();
+        if !missing.is_empty() {
+            return Err(ArchiveError::AccountsDoNotExist(missing).into());
+        }
+
+        for task in &archive.catalogue {
+            sqlx::query!(
+                "INSERT INTO CATALOGUE_TASK (id, organization, created_by, title, description, effort, min_age, skills)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                Uuid::from(task.id.ulid()),
+                Uuid::from(task.organization.ulid()),
+                Uuid::from(task.created_by.ulid()),
+                task.title,
+                task.description,
+                i32::try_from(task.effort)?,
+                task.requirements.min_age.map(i32::try_from).transpose()?,
+                &Vec::from_iter(task.requirements.skills.iter().cloned())
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for archived in &archive.organization_events {
+            sqlx::query!(
+                "INSERT INTO ORGANIZATION_EVENT (organization, payload, created)
+                VALUES ($1, $2, $3)",
+                Uuid::from(archive.organization.ulid()),
+                Json(&archived.event) as _,
+                archived.created
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        project_organization(&mut tx, archive.organization).await?;
+
+        for archived in &archive.task_events {
+            append_task_event(&mut tx, &archived.event, archived.created).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn query_for_purgeable(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> Result<Vec<OrganizationId>, anyhow::Error> {
+        Ok(sqlx::query_scalar!(
+            "SELECT organization FROM ARCHIVED_ORGANIZATION WHERE archived <= $1 ORDER BY archived",
+            archived_before
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(OrganizationId::from)
+        .collect())
+    }
+
+    //accounts are shared between organizations and are kept
+    async fn purge(
+        &self,
+        id: &OrganizationId,
+        archived_before: DateTime<Utc>,
+    ) -> Result<bool, anyhow::Error> {
+        let organization = Uuid::from(id.ulid());
+        let mut tx = self.pool.begin().await?;
+        //same lock as organization writes, an unarchive cannot slip in between the check and the deletes
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtext('jira-for-chores.organization.' || $1))",
+            id.ulid().to_string()
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let still_archived = sqlx::query!(
+            "DELETE FROM ARCHIVED_ORGANIZATION WHERE organization = $1 AND archived <= $2",
+            organization,
+            archived_before
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !still_archived {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "DELETE FROM TASK_REMINDER
+            WHERE task IN (SELECT id FROM TASK_INSTANCE WHERE organization = $1)",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM TASK_EVENT WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM TASK_INSTANCE WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM CATALOGUE_TASK WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM WEEKLY_STATS WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM INVITATION WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM ORGANIZATION_MEMBER WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "DELETE FROM ORGANIZATION_EVENT WHERE organization = $1",
+            organization
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
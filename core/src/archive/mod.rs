@@ -0,0 +1,5 @@
+pub mod format;
+pub mod infrastructure;
+pub mod service;
+
+pub use format::{ArchivedAccount, OrganizationArchive, ARCHIVE_VERSION};
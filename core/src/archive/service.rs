@@ -0,0 +1,102 @@
+use std::{collections::HashMap, future::Future};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{management::models::organization::OrganizationId, shared::account::AccountId};
+
+use super::format::OrganizationArchive;
+
+pub trait ArchiveRepository: Send + Sync + Clone + 'static {
+    fn export(
+        &self,
+        id: &OrganizationId,
+    ) -> impl Future<Output = Result<OrganizationArchive, anyhow::Error>> + Send;
+    fn restore(
+        &self,
+        archive: &OrganizationArchive,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    fn query_for_purgeable(
+        &self,
+        archived_before: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<OrganizationId>, anyhow::Error>> + Send;
+    //returns false when the organization was unarchived or archived again in the meantime
+    fn purge(
+        &self,
+        id: &OrganizationId,
+        archived_before: DateTime<Utc>,
+    ) -> impl Future<Output = Result<bool, anyhow::Error>> + Send;
+}
+
+pub struct ArchiveService<R>
+where
+    R: ArchiveRepository,
+{
+    repo: R,
+}
+
+impl<R> ArchiveService<R>
+where
+    R: ArchiveRepository,
+{
+    pub fn new(repo: R) -> Self {
+        Self { repo }
+    }
+
+    pub async fn export_organization(
+        &self,
+        id: OrganizationId,
+    ) -> Result<OrganizationArchive, anyhow::Error> {
+        self.repo.export(&id).await
+    }
+
+    pub async fn restore_organization(
+        &self,
+        archive: OrganizationArchive,
+        mode: RestoreMode,
+        accounts: HashMap<AccountId, AccountId>,
+    ) -> Result<OrganizationId, anyhow::Error> {
+        archive.check_version()?;
+        let archive = match mode {
+            RestoreMode::Original => archive,
+            RestoreMode::Copy => archive.with_new_ids(),
+        };
+        //archived accounts missing from the target database have to be mapped to existing ones
+        let archive = archive.with_accounts(accounts);
+
+        self.repo.restore(&archive).await?;
+        Ok(archive.organization)
+    }
+
+    //hard deletes organizations that have been archived for longer than the grace period
+    pub async fn purge_archived(
+        &self,
+        now: DateTime<Utc>,
+        grace: Duration,
+    ) -> Result<Purges, anyhow::Error> {
+        let archived_before = now - grace;
+        let mut out = Purges::default();
+        for id in self.repo.query_for_purgeable(archived_before).await? {
+            match self.repo.purge(&id, archived_before).await {
+                Ok(true) => out.organizations += 1,
+                Ok(false) => {}
+                //left archived for the next run
+                Err(_) => out.failed += 1,
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Purges {
+    pub organizations: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestoreMode {
+    //keeps every id, the target database must not contain the organization
+    Original,
+    //assigns new organization, tag, catalogue and task ids, accounts are kept
+    Copy,
+}
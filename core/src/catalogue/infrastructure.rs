@@ -1,6 +1,9 @@
 use sqlx::{postgres::PgPoolOptions, types::Uuid};
 
-use super::{service::CatalogueRepository, task::CatalogueTask};
+use super::{
+    service::CatalogueRepository,
+    task::{CatalogueTask, Requirements},
+};
 
 #[derive(Debug, Clone)]
 pub struct PostgressCatalogueRepository {
@@ -19,18 +22,18 @@ impl PostgressCatalogueRepository {
 }
 
 impl CatalogueRepository for PostgressCatalogueRepository {
-    async fn save(
-        &self,
-        task: &super::task::CatalogueTask,
-    ) -> Result<(), anyhow::Error> {
+    async fn save(&self, task: &super::task::CatalogueTask) -> Result<(), anyhow::Error> {
         sqlx::query!(
-            "INSERT INTO CATALOGUE_TASK (id, organization, created_by, title, description)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO CATALOGUE_TASK (id, organization, created_by, title, description, effort, min_age, skills)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
             Uuid::from(task.id.ulid()),
             Uuid::from(task.organization.ulid()),
             Uuid::from(task.created_by.ulid()),
             task.title,
-            task.description
+            task.description,
+            i32::try_from(task.effort)?,
+            task.requirements.min_age.map(i32::try_from).transpose()?,
+            &Vec::from_iter(task.requirements.skills.iter().cloned())
         ).execute(&self.pool).await?;
 
         Ok(())
@@ -41,32 +44,75 @@ impl CatalogueRepository for PostgressCatalogueRepository {
         id: &super::CatalogueTaskId,
     ) -> Result<super::task::CatalogueTask, anyhow::Error> {
         let record = sqlx::query!(
-            "SELECT id, organization, created_by, title, description
+            "SELECT id, organization, created_by, title, description, effort, min_age, skills
             FROM CATALOGUE_TASK 
             WHERE id = $1",
             Uuid::from(id.ulid())
-        ).fetch_one(&self.pool).await?;
+        )
+        .fetch_one(&self.pool)
+        .await?;
 
-        Ok(CatalogueTask{
+        Ok(CatalogueTask {
             id: record.id.into(),
             organization: record.organization.into(),
             created_by: record.created_by.into(),
             title: record.title,
             description: record.description,
+            effort: record.effort.try_into()?,
+            requirements: Requirements::new(
+                record.min_age.map(u32::try_from).transpose()?,
+                record.skills,
+            ),
         })
     }
 
-    async fn delete_by_id(
+    async fn save_effort(
         &self,
         id: &super::CatalogueTaskId,
+        effort: u32,
     ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "UPDATE CATALOGUE_TASK
+            SET effort = $2
+            WHERE id = $1",
+            Uuid::from(id.ulid()),
+            i32::try_from(effort)?
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_requirements(
+        &self,
+        id: &super::CatalogueTaskId,
+        requirements: &Requirements,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "UPDATE CATALOGUE_TASK
+            SET min_age = $2, skills = $3
+            WHERE id = $1",
+            Uuid::from(id.ulid()),
+            requirements.min_age.map(i32::try_from).transpose()?,
+            &Vec::from_iter(requirements.skills.iter().cloned())
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_by_id(&self, id: &super::CatalogueTaskId) -> Result<(), anyhow::Error> {
         sqlx::query!(
             "DELETE FROM 
             CATALOGUE_TASK
             WHERE id = $1",
             Uuid::from(id.ulid())
-        ).execute(&self.pool).await?;
+        )
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
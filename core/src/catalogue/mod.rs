@@ -2,4 +2,4 @@ pub mod infrastructure;
 pub mod service;
 pub mod task;
 
-pub use task::CatalogueTaskId;
+pub use task::{normalize_skills, CatalogueTaskId, Requirements, DEFAULT_EFFORT};
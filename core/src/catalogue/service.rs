@@ -1,8 +1,13 @@
 use std::future::Future;
 
-use crate::{management::models::organization::OrganizationId, shared::account::AccountId};
+use crate::{
+    management::{
+        application::ports::OrganizationRepository, models::organization::OrganizationId,
+    },
+    shared::account::AccountId,
+};
 
-use super::{task::CatalogueTask, CatalogueTaskId};
+use super::{task::CatalogueTask, CatalogueTaskId, Requirements};
 
 pub trait CatalogueRepository: Send + Sync + Clone + 'static {
     fn save(&self, task: &CatalogueTask) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
@@ -14,27 +19,43 @@ pub trait CatalogueRepository: Send + Sync + Clone + 'static {
         &self,
         id: &CatalogueTaskId,
     ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    fn save_effort(
+        &self,
+        id: &CatalogueTaskId,
+        effort: u32,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    fn save_requirements(
+        &self,
+        id: &CatalogueTaskId,
+        requirements: &Requirements,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
 }
 
-pub struct CatalogueService<R>
+pub struct CatalogueService<R, O>
 where
     R: CatalogueRepository,
+    O: OrganizationRepository,
 {
     repo: R,
+    org_repo: O,
 }
 
-impl<R> CatalogueService<R>
+impl<R, O> CatalogueService<R, O>
 where
     R: CatalogueRepository,
+    O: OrganizationRepository,
 {
-    pub fn new(repo: R) -> Self {
-        Self { repo }
+    pub fn new(repo: R, org_repo: O) -> Self {
+        Self { repo, org_repo }
     }
 
     pub async fn create_task(
         &self,
         command: CreateTaskCommand,
     ) -> Result<CatalogueTaskId, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        org.authorize_catalogue_write(command.created_by)?;
+
         let id = CatalogueTaskId::new();
         let task = CatalogueTask {
             id,
@@ -42,6 +63,8 @@ where
             created_by: command.created_by,
             title: command.title,
             description: command.description,
+            effort: command.effort,
+            requirements: command.requirements,
         };
 
         self.repo.save(&task).await?;
@@ -49,15 +72,49 @@ where
     }
 
     pub async fn get_task(&self, id: CatalogueTaskId) -> Result<CatalogueTask, anyhow::Error> {
-        Ok(self.repo.get_by_id(&id).await?)
+        self.repo.get_by_id(&id).await
     }
 
     pub async fn task_exists(&self, id: CatalogueTaskId) -> Result<bool, anyhow::Error> {
         Ok(self.repo.get_by_id(&id).await.is_ok())
     }
 
-    pub async fn delete_task(&self, id: CatalogueTaskId) -> Result<(), anyhow::Error> {
-        Ok(self.repo.delete_by_id(&id).await?)
+    pub async fn delete_task(
+        &self,
+        id: CatalogueTaskId,
+        requesting_account: AccountId,
+    ) -> Result<(), anyhow::Error> {
+        let task = self.repo.get_by_id(&id).await?;
+        let org = self.org_repo.find_org_by_id(task.organization).await?;
+        org.authorize_catalogue_write(requesting_account)?;
+
+        self.repo.delete_by_id(&id).await
+    }
+
+    pub async fn set_effort(
+        &self,
+        id: CatalogueTaskId,
+        effort: u32,
+        requesting_account: AccountId,
+    ) -> Result<(), anyhow::Error> {
+        let task = self.repo.get_by_id(&id).await?;
+        let org = self.org_repo.find_org_by_id(task.organization).await?;
+        org.authorize_catalogue_write(requesting_account)?;
+
+        self.repo.save_effort(&id, effort).await
+    }
+
+    pub async fn set_requirements(
+        &self,
+        id: CatalogueTaskId,
+        requirements: Requirements,
+        requesting_account: AccountId,
+    ) -> Result<(), anyhow::Error> {
+        let task = self.repo.get_by_id(&id).await?;
+        let org = self.org_repo.find_org_by_id(task.organization).await?;
+        org.authorize_catalogue_write(requesting_account)?;
+
+        self.repo.save_requirements(&id, &requirements).await
     }
 }
 
@@ -66,4 +123,6 @@ pub struct CreateTaskCommand {
     pub created_by: AccountId,
     pub title: String,
     pub description: String,
+    pub effort: u32,
+    pub requirements: Requirements,
 }
@@ -1,10 +1,12 @@
+use std::collections::BTreeSet;
+
 use serde::{Deserialize, Serialize};
 use sqlx::types::Uuid;
 use ulid::Ulid;
 
 use crate::{management::models::organization::OrganizationId, shared::account::AccountId};
 
-#[derive(Debug, Clone, PartialEq, Copy, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default, Serialize, Deserialize)]
 pub struct CatalogueTaskId(Ulid);
 
 impl CatalogueTaskId {
@@ -22,6 +24,43 @@ impl From<Uuid> for CatalogueTaskId {
     }
 }
 
+impl From<Ulid> for CatalogueTaskId {
+    fn from(value: Ulid) -> Self {
+        CatalogueTaskId(value)
+    }
+}
+
+//effort points of tasks created before tasks carried any
+pub const DEFAULT_EFFORT: u32 = 1;
+
+fn default_effort() -> u32 {
+    DEFAULT_EFFORT
+}
+
+//what a worker needs before the task can be assigned to them
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Requirements {
+    pub min_age: Option<u32>,
+    pub skills: BTreeSet<String>,
+}
+
+impl Requirements {
+    pub fn new(min_age: Option<u32>, skills: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            min_age,
+            skills: normalize_skills(skills),
+        }
+    }
+}
+
+//skills are compared by name, so "Uses stove " and "uses stove" are the same skill
+pub fn normalize_skills(skills: impl IntoIterator<Item = String>) -> BTreeSet<String> {
+    skills
+        .into_iter()
+        .map(|skill| skill.trim().to_lowercase())
+        .filter(|skill| !skill.is_empty())
+        .collect()
+}
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct CatalogueTask {
@@ -30,4 +69,9 @@ pub struct CatalogueTask {
     pub created_by: AccountId,
     pub title: String,
     pub description: String,
+    //how much work the task is compared to others in the catalogue
+    #[serde(default = "default_effort")]
+    pub effort: u32,
+    #[serde(default)]
+    pub requirements: Requirements,
 }
@@ -0,0 +1,163 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgPoolOptions, Connection, PgConnection};
+
+use super::{
+    run::{JobCounts, JobOutcome, JobRun},
+    service::{Acquired, JobLedger},
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgressJobLedger {
+    pool: sqlx::PgPool,
+    path: String,
+}
+
+impl PostgressJobLedger {
+    pub async fn new(path: &str) -> anyhow::Result<PostgressJobLedger> {
+        let pool = PgPoolOptions::new()
+            .test_before_acquire(false)
+            .connect(path)
+            .await?;
+
+        Ok(Self {
+            pool,
+            path: path.to_string(),
+        })
+    }
+}
+
+//advisory locks belong to a session, so every run gets its own connection instead of a pooled one
+pub struct PostgressJobLease {
+    connection: PgConnection,
+    job: String,
+    run: i64,
+}
+
+impl JobLedger for PostgressJobLedger {
+    type Lease = PostgressJobLease;
+
+    async fn acquire(
+        &self,
+        job: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<Acquired<PostgressJobLease>, anyhow::Error> {
+        let mut connection = PgConnection::connect(&self.path).await?;
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock(hashtext('jira-for-chores.' || $1)) as "locked!""#,
+            job
+        )
+        .fetch_one(&mut connection)
+        .await?;
+        if !locked {
+            connection.close().await?;
+            return Ok(Acquired::Locked);
+        }
+
+        //nobody else can be running the job while we hold the lock
+        sqlx::query!(
+            "UPDATE JOB_RUN
+            SET outcome = 'abandoned', finished = now()
+            WHERE job = $1 AND outcome = 'running'",
+            job
+        )
+        .execute(&mut connection)
+        .await?;
+
+        let ran = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM JOB_RUN
+                WHERE job = $1 AND window_start = $2 AND outcome = 'succeeded'
+            ) as "ran!""#,
+            job,
+            window_start
+        )
+        .fetch_one(&mut connection)
+        .await?;
+        if ran {
+            connection.close().await?;
+            return Ok(Acquired::AlreadyRan);
+        }
+
+        let run = sqlx::query_scalar!(
+            "INSERT INTO JOB_RUN (job, window_start, outcome)
+            VALUES ($1, $2, 'running')
+            RETURNING id",
+            job,
+            window_start
+        )
+        .fetch_one(&mut connection)
+        .await?;
+
+        Ok(Acquired::Run(PostgressJobLease {
+            connection,
+            job: job.to_string(),
+            run,
+        }))
+    }
+
+    async fn release(
+        &self,
+        mut lease: PostgressJobLease,
+        outcome: JobOutcome,
+        counts: JobCounts,
+        message: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "UPDATE JOB_RUN
+            SET finished = now(), outcome = $2, processed = $3, failed = $4, message = $5
+            WHERE id = $1",
+            lease.run,
+            outcome.as_str(),
+            i64::try_from(counts.processed)?,
+            i64::try_from(counts.failed)?,
+            message
+        )
+        .execute(&mut lease.connection)
+        .await?;
+
+        sqlx::query_scalar!(
+            "SELECT pg_advisory_unlock(hashtext('jira-for-chores.' || $1))",
+            lease.job
+        )
+        .fetch_one(&mut lease.connection)
+        .await?;
+        lease.connection.close().await?;
+
+        Ok(())
+    }
+
+    async fn history(&self, job: Option<&str>, limit: i64) -> Result<Vec<JobRun>, anyhow::Error> {
+        let records = sqlx::query!(
+            "SELECT id, job, window_start, started, finished, outcome, processed, failed, message
+            FROM JOB_RUN
+            WHERE $1::varchar IS NULL OR job = $1
+            ORDER BY id DESC
+            LIMIT $2",
+            job,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(JobRun {
+                    id: record.id,
+                    job: record.job,
+                    window_start: record.window_start,
+                    started: record.started,
+                    finished: record.finished,
+                    outcome: JobOutcome::parse(&record.outcome)
+                        .ok_or(anyhow!("unknown job outcome {}", record.outcome))?,
+                    counts: JobCounts {
+                        processed: record.processed.try_into()?,
+                        failed: record.failed.try_into()?,
+                    },
+                    message: record.message,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,5 @@
+pub mod infrastructure;
+pub mod run;
+pub mod service;
+
+pub use run::{JobCounts, JobFailure, JobOutcome, JobRun};
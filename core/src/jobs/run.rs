@@ -0,0 +1,70 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobOutcome {
+    Running,
+    Succeeded,
+    Failed,
+    //the process died while the run held the lock
+    Abandoned,
+}
+
+impl JobOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobOutcome::Running => "running",
+            JobOutcome::Succeeded => "succeeded",
+            JobOutcome::Failed => "failed",
+            JobOutcome::Abandoned => "abandoned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<JobOutcome> {
+        match value {
+            "running" => Some(JobOutcome::Running),
+            "succeeded" => Some(JobOutcome::Succeeded),
+            "failed" => Some(JobOutcome::Failed),
+            "abandoned" => Some(JobOutcome::Abandoned),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct JobCounts {
+    pub processed: usize,
+    pub failed: usize,
+}
+
+//a run that could not go on, with what it got through before it stopped
+#[derive(Debug)]
+pub struct JobFailure {
+    pub counts: JobCounts,
+    pub error: anyhow::Error,
+}
+
+impl From<anyhow::Error> for JobFailure {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            counts: JobCounts::default(),
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRun {
+    pub id: i64,
+    pub job: String,
+    pub window_start: DateTime<Utc>,
+    pub started: DateTime<Utc>,
+    pub finished: Option<DateTime<Utc>>,
+    pub outcome: JobOutcome,
+    pub counts: JobCounts,
+    pub message: Option<String>,
+}
+
+//start of the scheduling window `now` falls into, windows are aligned to the unix epoch
+pub fn window_start(now: DateTime<Utc>, window: Duration) -> DateTime<Utc> {
+    now.duration_trunc(window).unwrap_or(now)
+}
@@ -0,0 +1,107 @@
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+
+use super::run::{JobCounts, JobFailure, JobOutcome, JobRun};
+
+pub enum Acquired<L> {
+    Run(L),
+    //another process holds the job lock
+    Locked,
+    //the window already has a successful run
+    AlreadyRan,
+}
+
+pub trait JobLedger: Send + Sync + Clone + 'static {
+    //held for the duration of a run, dropping it releases the lock
+    type Lease: Send;
+
+    fn acquire(
+        &self,
+        job: &str,
+        window_start: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Acquired<Self::Lease>, anyhow::Error>> + Send;
+    fn release(
+        &self,
+        lease: Self::Lease,
+        outcome: JobOutcome,
+        counts: JobCounts,
+        message: Option<String>,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    fn history(
+        &self,
+        job: Option<&str>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<JobRun>, anyhow::Error>> + Send;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Ran(JobCounts),
+    Locked,
+    AlreadyRan,
+}
+
+pub struct JobService<L>
+where
+    L: JobLedger,
+{
+    ledger: L,
+}
+
+impl<L> JobService<L>
+where
+    L: JobLedger,
+{
+    pub fn new(ledger: L) -> Self {
+        Self { ledger }
+    }
+
+    //runs the job unless it is already running elsewhere or has succeeded in this window
+    pub async fn run_once<F, Fut, E>(
+        &self,
+        job: &str,
+        window_start: DateTime<Utc>,
+        run: F,
+    ) -> Result<JobStatus, anyhow::Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<JobCounts, E>>,
+        E: Into<JobFailure>,
+    {
+        let lease = match self.ledger.acquire(job, window_start).await? {
+            Acquired::Run(lease) => lease,
+            Acquired::Locked => return Ok(JobStatus::Locked),
+            Acquired::AlreadyRan => return Ok(JobStatus::AlreadyRan),
+        };
+
+        match run().await {
+            Ok(counts) => {
+                self.ledger
+                    .release(lease, JobOutcome::Succeeded, counts, None)
+                    .await?;
+                Ok(JobStatus::Ran(counts))
+            }
+            Err(failure) => {
+                let failure = failure.into();
+                self.ledger
+                    .release(
+                        lease,
+                        JobOutcome::Failed,
+                        failure.counts,
+                        Some(format!("{:#}", failure.error)),
+                    )
+                    .await?;
+                Err(failure.error)
+            }
+        }
+    }
+
+    pub async fn history(
+        &self,
+        job: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<JobRun>, anyhow::Error> {
+        self.ledger.history(job, limit).await
+    }
+}
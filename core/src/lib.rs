@@ -1,5 +1,7 @@
+pub mod archive;
 pub mod catalogue;
-mod management;
+pub mod jobs;
+pub mod management;
 pub mod shared;
 
 pub use management::application::ports::*;
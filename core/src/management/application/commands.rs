@@ -1,12 +1,23 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::Deserialize;
 
 use crate::{
     catalogue::CatalogueTaskId,
     management::models::{
-        organization::{AccountType, OrganizationId, TagId, TaskAssignmentType},
-        task::TaskId,
+        availability::{Unavailability, UnavailabilityId},
+        invitation::InvitationId,
+        organization::{
+            AccountType, AssignmentType, OrganizationId, PendingTaskHandling, ReassignmentPolicy,
+            TagId, TaskAssignmentType,
+        },
+        policy::Capability,
+        settings::OrganizationSettings,
+        statistics::WeeklyStats,
+        task::{ClaimDeadline, TaskId, TaskInstance},
+        trade::TradeId,
+        workload::{CapTarget, WorkloadCaps},
     },
     shared::account::AccountId,
 };
@@ -24,17 +35,289 @@ pub struct AccountLinkCommand {
     pub account_type: AccountType,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateInvitationCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account_type: AccountType,
+    pub tags: HashSet<TagId>,
+    //None for a code that can be used any number of times
+    pub max_uses: Option<u32>,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedeemInvitationCommand {
+    pub code: String,
+    pub requesting_account: AccountId,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RevokeInvitationCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub invitation: InvitationId,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetRoleCapabilitiesCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account_type: AccountType,
+    pub capabilities: HashSet<Capability>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeRoleCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account: AccountId,
+    pub account_type: AccountType,
+}
+
+//share is a percentage of a full share of effort, 50 means half as much as everyone else
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetHandicapCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account: AccountId,
+    pub share: u32,
+}
+
+//caps with no limit at all remove the ones set for the target before
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetCapsCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub target: CapTarget,
+    pub caps: WorkloadCaps,
+}
+
+//replaces the birthdate and skills catalogue requirements are checked against
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetProfileCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account: AccountId,
+    pub birthdate: Option<NaiveDate>,
+    pub skills: Vec<String>,
+}
+
+//accounts may record their own unavailability, anyone else needs to manage accounts
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddUnavailabilityCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account: AccountId,
+    pub unavailability: Unavailability,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoveUnavailabilityCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub id: UnavailabilityId,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnlinkAccountCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub account: AccountId,
+    pub pending_tasks: PendingTaskHandling,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaveOrganizationCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub pending_tasks: PendingTaskHandling,
+}
+
+//cancels or reassigns the tasks left behind by accounts that are no longer linked
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolveOrphanedTasksCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub pending_tasks: PendingTaskHandling,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddTagCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenameTagCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tag: TagId,
+    pub name: String,
+}
+
+//None makes the tag a top level tag again
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetTagParentCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tag: TagId,
+    pub parent: Option<TagId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoveTagCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tag: TagId,
+}
+
+//adds or removes a worker or editor of a tag
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagMemberCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tag: TagId,
+    pub account: AccountId,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct FinishTaskCommand {
     pub task: TaskId,
     pub requesting_account: AccountId,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddTimeCommand {
+    pub task: TaskId,
+    pub requesting_account: AccountId,
+    #[serde(with = "crate::shared::serde_duration::seconds")]
+    pub duration: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct AssignTaskCommand {
     pub organization: OrganizationId,
     pub tasks: Vec<CatalogueTaskId>,
     pub requesting_account: AccountId,
-    pub assignment_type: TaskAssignmentType,
+    //None falls back to the organization settings for both
+    pub assignment_type: Option<TaskAssignmentType>,
+    pub tags: HashSet<TagId>,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+//expires falls back to the organization settings, the claim deadline does not
+#[derive(Debug, Clone)]
+pub struct PostTasksCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tags: HashSet<TagId>,
+    pub tasks: Vec<CatalogueTaskId>,
+    pub expires: Option<DateTime<Utc>>,
+    pub claim_deadline: Option<ClaimDeadline>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimTaskCommand {
+    pub task: TaskId,
+    pub requesting_account: AccountId,
+}
+
+//without a counterparty task the offered task is simply handed over
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProposeTradeCommand {
+    pub task: TaskId,
+    pub requesting_account: AccountId,
+    pub counterparty: AccountId,
+    pub counterparty_task: Option<TaskId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub trade: TradeId,
+}
+
+//list a task once for every time it has to be done in the week
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanWeekCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub tags: HashSet<TagId>,
+    pub tasks: Vec<CatalogueTaskId>,
+    //any day of the week to plan, None plans the current week
+    pub week: Option<NaiveDate>,
+    //only tighten the caps set for the organization
+    #[serde(default)]
+    pub caps: HashMap<AccountId, WorkloadCaps>,
+}
+
+//the tasks of a preview, assignees can be changed with TaskInstance::with_assigned_to
+#[derive(Debug, Clone)]
+pub struct ConfirmAssignmentCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    //the same assignment type and tags the preview was made with
+    pub assignment_type: Option<TaskAssignmentType>,
     pub tags: HashSet<TagId>,
+    pub tasks: Vec<TaskInstance>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SendRemindersCommand {
+    pub thresholds: Vec<Duration>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetReassignmentPolicyCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub policy: Option<ReassignmentPolicy>,
+}
+
+//archiving and unarchiving are left to the owner
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchiveOrganizationCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub archive: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangeSettingsCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub settings: OrganizationSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddRepeatingTaskCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub assigned_to: AssignmentType,
+    pub tasks: Vec<CatalogueTaskId>,
+    pub period_days: u64,
+    pub starts: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SendDigestsCommand {
+    pub last_week: Vec<WeeklyStats>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferOwnershipCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub new_owner: AccountId,
+}
+
+//answers an ownership offer made to the requesting account
+#[derive(Debug, Clone, Deserialize)]
+pub struct RespondToOwnershipCommand {
+    pub organization: OrganizationId,
+    pub requesting_account: AccountId,
+    pub accept: bool,
 }
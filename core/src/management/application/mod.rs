@@ -1,6 +1,8 @@
 pub mod commands;
+pub mod notifications;
 pub mod ports;
 pub mod service;
+pub mod statistics;
 pub mod views;
 
 pub use service::*;
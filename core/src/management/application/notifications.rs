@@ -0,0 +1,144 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Locale, Utc};
+use chrono_tz::Tz;
+
+use crate::{
+    catalogue::CatalogueTaskId,
+    management::models::{
+        organization::OrganizationId,
+        statistics::WeeklyStats,
+        task::{TaskId, TaskStatus},
+    },
+    shared::account::AccountId,
+};
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Notification {
+    TaskExpiring {
+        task: TaskId,
+        catalogue_task: CatalogueTaskId,
+        expires: DateTime<Utc>,
+        threshold: Duration,
+    },
+    //sent to whoever assigned a task that expired or was rejected
+    TaskReassigned {
+        task: TaskId,
+        catalogue_task: CatalogueTaskId,
+        status: TaskStatus,
+        previous: AccountId,
+        reassigned_to: Vec<AccountId>,
+    },
+    //sent to the account the owner wants to hand the organization to
+    OwnershipOffered {
+        organization: OrganizationId,
+        name: String,
+        from: AccountId,
+    },
+    //sent to the owner once the offer was answered
+    OwnershipAnswered {
+        organization: OrganizationId,
+        name: String,
+        to: AccountId,
+        accepted: bool,
+    },
+    //pending tasks of one account, soonest deadline first, with last week's numbers per organization
+    Digest {
+        tasks: Vec<DigestEntry>,
+        last_week: Vec<WeeklyStats>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    pub task: TaskId,
+    pub catalogue_task: CatalogueTaskId,
+    pub expires: Option<DateTime<Utc>>,
+    //deadlines are shown the way the task's organization reads dates
+    pub time_zone: Tz,
+    pub locale: Locale,
+}
+
+impl Display for Notification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Notification::TaskExpiring {
+                task,
+                expires,
+                threshold,
+                ..
+            } => write!(
+                f,
+                "task {} expires at {} (less than {} minutes left)",
+                task.ulid(),
+                expires,
+                threshold.num_minutes()
+            ),
+            Notification::TaskReassigned {
+                task,
+                status,
+                previous,
+                reassigned_to,
+                ..
+            } => match reassigned_to.is_empty() {
+                true => write!(
+                    f,
+                    "task {} assigned to {} was {} and could not be reassigned",
+                    task.ulid(),
+                    previous.ulid(),
+                    status.as_str()
+                ),
+                false => write!(
+                    f,
+                    "task {} assigned to {} was {} and has been reassigned to {}",
+                    task.ulid(),
+                    previous.ulid(),
+                    status.as_str(),
+                    reassigned_to
+                        .iter()
+                        .map(|account| account.ulid().to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            },
+            Notification::OwnershipOffered { name, from, .. } => {
+                write!(f, "{} offered you ownership of {}", from.ulid(), name)
+            }
+            Notification::OwnershipAnswered {
+                name, to, accepted, ..
+            } => match accepted {
+                true => write!(f, "{} is now the owner of {}", to.ulid(), name),
+                false => write!(f, "{} declined ownership of {}", to.ulid(), name),
+            },
+            Notification::Digest { tasks, last_week } => {
+                write!(f, "{} pending tasks", tasks.len())?;
+                for stats in last_week {
+                    write!(
+                        f,
+                        "\n  last week in {}: finished {} of {} assigned, rejected {}, expired {}",
+                        stats.organization.ulid(),
+                        stats.finished,
+                        stats.assigned,
+                        stats.rejected,
+                        stats.expired
+                    )?;
+                }
+                for entry in tasks {
+                    match entry.expires {
+                        Some(expires) => write!(
+                            f,
+                            "\n  task {} due {}",
+                            entry.task.ulid(),
+                            expires
+                                .with_timezone(&entry.time_zone)
+                                .format_localized("%c", entry.locale)
+                        )?,
+                        None => write!(f, "\n  task {}", entry.task.ulid())?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,10 +1,21 @@
 use std::future::Future;
 
-use crate::management::models::{
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::{
+    management::models::{
         events::{OrganizationEvent, TaskEvent},
         organization::{Organization, OrganizationId},
+        statistics::{TaskStream, WeeklyStats},
         task::{TaskId, TaskInstance},
-    };
+    },
+    shared::account::AccountId,
+};
+
+use super::{
+    notifications::Notification,
+    views::{AuditEntry, Membership},
+};
 
 pub trait TaskRepository: Send + Sync + Clone + 'static {
     fn handle(&self, event: TaskEvent) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
@@ -13,6 +24,7 @@ pub trait TaskRepository: Send + Sync + Clone + 'static {
         events: Vec<TaskEvent>,
     ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
     fn publish(&self, event: TaskEvent) -> Result<(), anyhow::Error>;
+    //the expired, pending and expiring queries leave out archived organizations
     fn query_for_expired_tasks(
         &self,
     ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
@@ -20,21 +32,114 @@ pub trait TaskRepository: Send + Sync + Clone + 'static {
         &self,
         id: TaskId,
     ) -> impl Future<Output = Result<TaskInstance, anyhow::Error>> + Send;
+    fn query_for_pending_tasks(
+        &self,
+    ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
+    fn query_for_pending_tasks_of(
+        &self,
+        account: AccountId,
+    ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
+    fn query_for_tasks_expiring_before(
+        &self,
+        deadline: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
+    fn query_for_open_tasks(
+        &self,
+        organization: OrganizationId,
+    ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
+    //open tasks whose claim deadline passed before the given time
+    fn query_for_unclaimed_tasks(
+        &self,
+        before: DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<TaskInstance>, anyhow::Error>> + Send;
+    //returns false when the reminder was already recorded
+    fn record_reminder(
+        &self,
+        task: TaskId,
+        threshold: Duration,
+    ) -> impl Future<Output = Result<bool, anyhow::Error>> + Send;
+    fn forget_reminder(
+        &self,
+        task: TaskId,
+        threshold: Duration,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
 }
 
 pub trait OrganizationRepository: Send + Sync + Clone + 'static {
     fn handle(
         &self,
+        organization: OrganizationId,
         event: OrganizationEvent,
     ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
     fn handle_many(
         &self,
+        organization: OrganizationId,
         events: Vec<OrganizationEvent>,
     ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    //organization and task events in one transaction, either all of them are written or none
+    fn handle_assignment(
+        &self,
+        organization: OrganizationId,
+        events: Vec<OrganizationEvent>,
+        tasks: Vec<TaskEvent>,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
     fn publish(&self, event: OrganizationEvent);
     fn query_for_pending_task_repeats(&self) -> impl Future<Output = Vec<Organization>> + Send;
     fn find_org_by_id(
         &self,
         id: OrganizationId,
     ) -> impl Future<Output = Result<Organization, anyhow::Error>> + Send;
+    //every event of the organization, oldest first
+    fn query_for_history(
+        &self,
+        id: OrganizationId,
+    ) -> impl Future<Output = Result<Vec<AuditEntry>, anyhow::Error>> + Send;
+    fn find_org_by_invitation(
+        &self,
+        code: &str,
+    ) -> impl Future<Output = Result<Option<OrganizationId>, anyhow::Error>> + Send;
+    fn query_for_members(
+        &self,
+        id: OrganizationId,
+    ) -> impl Future<Output = Result<Vec<Membership>, anyhow::Error>> + Send;
+    fn query_for_memberships(
+        &self,
+        account: AccountId,
+    ) -> impl Future<Output = Result<Vec<Membership>, anyhow::Error>> + Send;
+}
+
+pub trait StatisticsRepository: Send + Sync + Clone + 'static {
+    fn query_for_first_task_event(
+        &self,
+    ) -> impl Future<Output = Result<Option<DateTime<Utc>>, anyhow::Error>> + Send;
+    fn query_for_last_computed_week(
+        &self,
+    ) -> impl Future<Output = Result<Option<NaiveDate>, anyhow::Error>> + Send;
+    //every event up to the end of the week of each task that has an event during the week
+    fn query_for_task_streams(
+        &self,
+        week_start: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<TaskStream>, anyhow::Error>> + Send;
+    fn save_week(
+        &self,
+        week_start: NaiveDate,
+        stats: Vec<WeeklyStats>,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
+    fn query_for_week(
+        &self,
+        week_start: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<WeeklyStats>, anyhow::Error>> + Send;
+    fn query_for_organization(
+        &self,
+        organization: OrganizationId,
+        since: NaiveDate,
+    ) -> impl Future<Output = Result<Vec<WeeklyStats>, anyhow::Error>> + Send;
+}
+
+pub trait Notifier: Send + Sync + Clone + 'static {
+    fn notify(
+        &self,
+        account: AccountId,
+        notification: Notification,
+    ) -> impl Future<Output = Result<(), anyhow::Error>> + Send;
 }
@@ -1,39 +1,58 @@
+use chrono::{DateTime, Duration, Utc};
 use thiserror::Error;
 
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
+
 use crate::{
     management::models::{
+        availability::{AccountUnavailability, UnavailabilityId},
+        events::{OrganizationEvent, TaskEvent},
+        invitation::Invitation,
         organization::{
-            AccountType, Organization, OrganizationError, OrganizationId, TagId, TaskAssignmentType,
+            Assignment, Organization, OrganizationError, OrganizationId, PendingTaskHandling, TagId,
         },
-        task::{TaskDomainError, TaskId},
+        policy::{Capability, Scope},
+        settings::OrganizationSettings,
+        task::{TaskDomainError, TaskId, TaskInstance, TaskStatus},
+        trade::{Trade, TradeId},
+        workload::{CapTarget, WorkloadCaps},
     },
-    shared::{account::AccountId},
+    shared::account::AccountId,
 };
 
 use super::{
     commands::*,
-    ports::{OrganizationRepository, TaskRepository},
+    notifications::{DigestEntry, Notification},
+    ports::{Notifier, OrganizationRepository, TaskRepository},
+    views::{
+        AccountOverview, AssignmentPreview, AuditEntry, Digests, Expiries, FallBacks, Membership,
+        OrganizationMembership, PendingTask, Reminders, RepeatsAssigned,
+    },
 };
 
 #[derive(Debug, Clone)]
-pub struct ManagementService<T, O>
+pub struct ManagementService<T, O, N>
 where
     T: TaskRepository,
     O: OrganizationRepository,
+    N: Notifier,
 {
     task_repo: T,
     org_repo: O,
+    notifier: N,
 }
 
-impl<R, O> ManagementService<R, O>
+impl<R, O, N> ManagementService<R, O, N>
 where
     R: TaskRepository,
     O: OrganizationRepository,
+    N: Notifier,
 {
-    pub fn new(task_repo: R, org_repo: O) -> Self {
+    pub fn new(task_repo: R, org_repo: O, notifier: N) -> Self {
         Self {
             task_repo,
             org_repo,
+            notifier,
         }
     }
 
@@ -42,51 +61,1120 @@ where
         command: CreateOrgCommand,
     ) -> Result<OrganizationId, anyhow::Error> {
         let org = Organization::create(command.name, command.requesting_account)?;
-        self.org_repo.handle_many(org.into_create_event()?).await?;
-        Ok(org.id().clone())
+        let id = *org.id();
+        self.org_repo
+            .handle_many(id, org.into_create_event()?)
+            .await?;
+        Ok(id)
     }
 
     pub async fn link_account(&self, command: AccountLinkCommand) -> Result<(), anyhow::Error> {
         let org = self.org_repo.find_org_by_id(command.orgainzation).await?;
         self.org_repo
-            .handle(org.link_account(
-                command.requesting_account,
-                command.account,
-                command.account_type,
-            )?)
+            .handle(
+                command.orgainzation,
+                org.link_account(
+                    command.requesting_account,
+                    command.account,
+                    command.account_type,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn create_invitation(
+        &self,
+        command: CreateInvitationCommand,
+    ) -> Result<Invitation, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let event = org.create_invitation(
+            command.requesting_account,
+            command.account_type,
+            command.tags,
+            command.max_uses,
+            command.expires,
+        )?;
+        self.org_repo
+            .handle(command.organization, event.clone())
+            .await?;
+
+        let org = org.apply(&event);
+        Ok(org
+            .invitations()
+            .last()
+            .cloned()
+            .ok_or(OrganizationError::InvitationDoesNotExist)?)
+    }
+
+    pub async fn redeem_invitation(
+        &self,
+        command: RedeemInvitationCommand,
+    ) -> Result<OrganizationId, anyhow::Error> {
+        let organization = self
+            .org_repo
+            .find_org_by_invitation(&command.code)
+            .await?
+            .ok_or(OrganizationError::InvitationDoesNotExist)?;
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        self.org_repo
+            .handle_many(
+                organization,
+                org.redeem_invitation(&command.code, command.requesting_account, Utc::now())?,
+            )
+            .await?;
+        Ok(organization)
+    }
+
+    pub async fn invitations(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<Invitation>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        Ok(org.invitations().to_vec())
+    }
+
+    pub async fn revoke_invitation(
+        &self,
+        command: RevokeInvitationCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.revoke_invitation(command.requesting_account, command.invitation)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_role_capabilities(
+        &self,
+        command: SetRoleCapabilitiesCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_role_capabilities(
+                    command.requesting_account,
+                    command.account_type,
+                    command.capabilities,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn capabilities(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<HashSet<Capability>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        Ok(org.capabilities_of(requesting_account))
+    }
+
+    pub async fn change_role(&self, command: ChangeRoleCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.change_role(
+                    command.requesting_account,
+                    command.account,
+                    command.account_type,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_handicap(&self, command: SetHandicapCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_handicap(command.requesting_account, command.account, command.share)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_caps(&self, command: SetCapsCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_caps(command.requesting_account, command.target, command.caps)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn caps(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<(CapTarget, WorkloadCaps)>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        Ok(org
+            .caps()
+            .iter()
+            .map(|(target, caps)| (*target, *caps))
+            .collect())
+    }
+
+    pub async fn set_profile(&self, command: SetProfileCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_profile(
+                    command.requesting_account,
+                    command.account,
+                    command.birthdate,
+                    command.skills,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_unavailability(
+        &self,
+        command: AddUnavailabilityCommand,
+    ) -> Result<UnavailabilityId, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let event = org.add_unavailability(
+            command.requesting_account,
+            command.account,
+            command.unavailability,
+        )?;
+        let id = match &event {
+            OrganizationEvent::UnavailabilityAdded { id, .. } => *id,
+            _ => return Err(OrganizationError::UnavailabilityDoesNotExist.into()),
+        };
+        self.org_repo.handle(command.organization, event).await?;
+        Ok(id)
+    }
+
+    pub async fn remove_unavailability(
+        &self,
+        command: RemoveUnavailabilityCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.remove_unavailability(command.requesting_account, command.id)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn unavailability(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<AccountUnavailability>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        Ok(org.unavailability().to_vec())
+    }
+
+    pub async fn members(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<Membership>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        self.org_repo.query_for_members(organization).await
+    }
+
+    //an account can only look at its own overview
+    pub async fn account_overview(
+        &self,
+        requesting_account: AccountId,
+    ) -> Result<AccountOverview, anyhow::Error> {
+        let memberships = self
+            .org_repo
+            .query_for_memberships(requesting_account)
+            .await?;
+        let mut organizations = Vec::with_capacity(memberships.len());
+        for membership in memberships {
+            let org = self
+                .org_repo
+                .find_org_by_id(membership.organization)
+                .await?;
+            organizations.push(OrganizationMembership {
+                organization: membership.organization,
+                name: org.name().to_string(),
+                account_type: membership.account_type,
+                archived: org.archived().is_some(),
+            });
+        }
+
+        let pending_tasks = self
+            .task_repo
+            .query_for_pending_tasks_of(requesting_account)
+            .await?
+            .iter()
+            .filter(|task| {
+                organizations
+                    .iter()
+                    .any(|org| org.organization == task.organization())
+            })
+            .map(PendingTask::from)
+            .collect();
+
+        Ok(AccountOverview {
+            account: requesting_account,
+            organizations,
+            pending_tasks,
+        })
+    }
+
+    pub async fn organization_history(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize(
+            requesting_account,
+            Capability::OrganizationConfigure,
+            Scope::Organization,
+        )?;
+        self.org_repo.query_for_history(organization).await
+    }
+
+    pub async fn unlink_account(&self, command: UnlinkAccountCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let events = org.unlink_account(command.requesting_account, command.account)?;
+        self.remove_account(
+            org,
+            events,
+            command.account,
+            command.requesting_account,
+            &command.pending_tasks,
+        )
+        .await
+    }
+
+    pub async fn leave_organization(
+        &self,
+        command: LeaveOrganizationCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let events = org.leave(command.requesting_account)?;
+        self.remove_account(
+            org,
+            events,
+            command.requesting_account,
+            command.requesting_account,
+            &command.pending_tasks,
+        )
+        .await
+    }
+
+    async fn remove_account(
+        &self,
+        org: Organization,
+        events: Vec<OrganizationEvent>,
+        account: AccountId,
+        requesting_account: AccountId,
+        handling: &PendingTaskHandling,
+    ) -> Result<(), anyhow::Error> {
+        let mut pending = Vec::new();
+        for task in org.pending_tasks_of(account) {
+            pending.push(self.task_repo.find_task_by_id(task).await?);
+        }
+        let id = *org.id();
+        let unlinked = events.iter().fold(org, |org, event| org.apply(event));
+        let (task_events, rotations) = unlinked.release_tasks(
+            requesting_account,
+            &pending,
+            handling,
+            Utc::now(),
+            &mut rand::rng(),
+        )?;
+
+        self.org_repo
+            .handle_assignment(
+                id,
+                events.into_iter().chain(rotations).collect(),
+                task_events,
+            )
+            .await
+    }
+
+    pub async fn resolve_orphaned_tasks(
+        &self,
+        command: ResolveOrphanedTasksCommand,
+    ) -> Result<usize, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let mut orphaned = Vec::new();
+        for &task in org.orphaned_tasks() {
+            orphaned.push(self.task_repo.find_task_by_id(task).await?);
+        }
+        let (events, rotations) = org.release_orphaned_tasks(
+            command.requesting_account,
+            &orphaned,
+            &command.pending_tasks,
+            Utc::now(),
+            &mut rand::rng(),
+        )?;
+        let resolved = events
+            .iter()
+            .filter(|event| matches!(event, TaskEvent::Cancelled { .. }))
+            .count();
+
+        self.org_repo
+            .handle_assignment(command.organization, rotations, events)
+            .await?;
+        Ok(resolved)
+    }
+
+    pub async fn add_tag(&self, command: AddTagCommand) -> Result<TagId, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let events = org.add_tag(command.name, command.requesting_account)?;
+        let id = events
+            .iter()
+            .find_map(|event| match event {
+                OrganizationEvent::TagAdded { tag_id, .. } => Some(*tag_id),
+                _ => None,
+            })
+            .ok_or(OrganizationError::TagDoesNotExist)?;
+        self.org_repo
+            .handle_many(command.organization, events)
+            .await?;
+        Ok(id)
+    }
+
+    pub async fn rename_tag(&self, command: RenameTagCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.rename_tag(command.tag, command.requesting_account, command.name)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_tag_parent(&self, command: SetTagParentCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_tag_parent(command.tag, command.requesting_account, command.parent)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_tag(&self, command: RemoveTagCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle_many(
+                command.organization,
+                org.remove_tag(command.tag, command.requesting_account)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_worker_to_tag(&self, command: TagMemberCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.add_worker_to_tag(command.tag, command.requesting_account, command.account)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_worker_from_tag(
+        &self,
+        command: TagMemberCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.remove_worker_from_tag(
+                    command.tag,
+                    command.requesting_account,
+                    command.account,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_editor_to_tag(&self, command: TagMemberCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.add_editor_to_tag(command.tag, command.requesting_account, command.account)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove_editor_from_tag(
+        &self,
+        command: TagMemberCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.remove_editor_from_tag(
+                    command.tag,
+                    command.requesting_account,
+                    command.account,
+                )?,
+            )
             .await?;
         Ok(())
     }
 
     pub async fn assign_tasks(&self, command: AssignTaskCommand) -> Result<(), anyhow::Error> {
         let org = self.org_repo.find_org_by_id(command.organization).await?;
-        let tasks = org.assign_tasks_to_tags(
+        let assignment = Self::propose_assignment(&org, &command)?;
+        self.org_repo
+            .handle_assignment(
+                command.organization,
+                assignment.events,
+                assignment.tasks.iter().map(|task| task.create()).collect(),
+            )
+            .await
+    }
+
+    //who would get what, nothing is written and rotations do not move until the preview is confirmed
+    pub async fn preview_assign_tasks(
+        &self,
+        command: AssignTaskCommand,
+    ) -> Result<AssignmentPreview, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let assignment = Self::propose_assignment(&org, &command)?;
+        Ok(AssignmentPreview {
+            tasks: assignment.tasks,
+            ineligible: assignment.ineligible,
+        })
+    }
+
+    pub async fn confirm_assignment(
+        &self,
+        command: ConfirmAssignmentCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let assignment = org.confirm_assignment(
             &command.requesting_account,
             &command.tags,
             &command.tasks,
-            &command.assignment_type,
+            command
+                .assignment_type
+                .as_ref()
+                .unwrap_or(&org.settings().default_assignment_type),
+            Utc::now(),
         )?;
-        self.task_repo
-            .handle_many(tasks.iter().map(|task| task.create()).collect())
+        self.org_repo
+            .handle_assignment(
+                command.organization,
+                assignment.events,
+                assignment.tasks.iter().map(|task| task.create()).collect(),
+            )
+            .await
+    }
+
+    //nothing is written, the plan can be tweaked and confirmed like any other preview
+    pub async fn preview_week_plan(
+        &self,
+        command: PlanWeekCommand,
+    ) -> Result<AssignmentPreview, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let assignment = Self::plan(&org, &command)?;
+        Ok(AssignmentPreview {
+            tasks: assignment.tasks,
+            ineligible: assignment.ineligible,
+        })
+    }
+
+    //the whole week is written in one batch or not at all
+    pub async fn plan_week(
+        &self,
+        command: PlanWeekCommand,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let assignment = Self::plan(&org, &command)?;
+        self.org_repo
+            .handle_assignment(
+                command.organization,
+                assignment.events,
+                assignment.tasks.iter().map(|task| task.create()).collect(),
+            )
             .await?;
-        Ok(())
+        Ok(assignment.tasks)
+    }
+
+    fn plan(
+        org: &Organization,
+        command: &PlanWeekCommand,
+    ) -> Result<Assignment, OrganizationError> {
+        let now = Utc::now();
+        org.plan_week(
+            &command.requesting_account,
+            &command.tags,
+            &command.tasks,
+            command
+                .week
+                .unwrap_or_else(|| org.settings().local_date(now)),
+            now,
+            &command.caps,
+        )
+    }
+
+    fn propose_assignment(
+        org: &Organization,
+        command: &AssignTaskCommand,
+    ) -> Result<Assignment, OrganizationError> {
+        let settings = org.settings();
+        let now = Utc::now();
+        org.assign_tasks_to_tags(
+            &command.requesting_account,
+            &command.tags,
+            &command.tasks,
+            command
+                .assignment_type
+                .as_ref()
+                .unwrap_or(&settings.default_assignment_type),
+            now,
+            command.expires.or_else(|| settings.default_expires(now)),
+            &mut rand::rng(),
+        )
     }
 
     pub async fn finish_task(&self, command: FinishTaskCommand) -> Result<(), anyhow::Error> {
         let task = self.task_repo.find_task_by_id(command.task).await?;
+        self.ensure_active(&task).await?;
         self.task_repo
             .handle(task.finish(command.requesting_account)?)
             .await?;
         Ok(())
     }
 
+    pub async fn add_time(&self, command: AddTimeCommand) -> Result<(), anyhow::Error> {
+        let task = self.task_repo.find_task_by_id(command.task).await?;
+        let org = self.org_repo.find_org_by_id(task.organization()).await?;
+        self.task_repo
+            .handle(org.add_time_to_task(command.requesting_account, &task, command.duration)?)
+            .await?;
+        Ok(())
+    }
+
     pub async fn reject_task(&self, command: FinishTaskCommand) -> Result<(), anyhow::Error> {
         let task = self.task_repo.find_task_by_id(command.task).await?;
+        self.ensure_active(&task).await?;
+        let event = task.reject(command.requesting_account)?;
+        self.reassign(&task, event).await
+    }
+
+    pub async fn post_tasks(
+        &self,
+        command: PostTasksCommand,
+    ) -> Result<Vec<TaskId>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let expires = command
+            .expires
+            .or_else(|| org.settings().default_expires(Utc::now()));
+        let posted = org.post_tasks(
+            command.requesting_account,
+            &command.tags,
+            &command.tasks,
+            expires,
+            command.claim_deadline,
+        )?;
+        self.task_repo
+            .handle_many(posted.iter().map(|task| task.post()).collect())
+            .await?;
+        Ok(posted.iter().map(|task| task.id()).collect())
+    }
+
+    pub async fn open_tasks(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        self.task_repo.query_for_open_tasks(organization).await
+    }
+
+    //the repository checks the claim again under a row lock, so of two claims at once only one goes through
+    pub async fn claim_task(&self, command: ClaimTaskCommand) -> Result<(), anyhow::Error> {
+        let task = self.task_repo.find_task_by_id(command.task).await?;
+        let org = self.org_repo.find_org_by_id(task.organization()).await?;
+        self.task_repo
+            .handle(org.claim_task(command.requesting_account, &task, Utc::now())?)
+            .await
+    }
+
+    pub async fn unclaim_task(&self, command: ClaimTaskCommand) -> Result<(), anyhow::Error> {
+        let task = self.task_repo.find_task_by_id(command.task).await?;
+        let org = self.org_repo.find_org_by_id(task.organization()).await?;
         self.task_repo
-            .handle(task.reject(command.requesting_account)?)
+            .handle(org.unclaim_task(command.requesting_account, &task)?)
+            .await
+    }
+
+    pub async fn propose_trade(
+        &self,
+        command: ProposeTradeCommand,
+    ) -> Result<TradeId, anyhow::Error> {
+        let offered = self.task_repo.find_task_by_id(command.task).await?;
+        let requested = match command.counterparty_task {
+            Some(task) => Some(self.task_repo.find_task_by_id(task).await?),
+            None => None,
+        };
+        let org = self.org_repo.find_org_by_id(offered.organization()).await?;
+        let event = org.propose_trade(
+            command.requesting_account,
+            command.counterparty,
+            &offered,
+            requested.as_ref(),
+            Utc::now(),
+        )?;
+        let id = match &event {
+            OrganizationEvent::TradeProposed { id, .. } => *id,
+            _ => return Err(OrganizationError::TradeDoesNotExist.into()),
+        };
+        self.org_repo.handle(offered.organization(), event).await?;
+        Ok(id)
+    }
+
+    pub async fn accept_trade(&self, command: TradeCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let event = org.accept_trade(command.requesting_account, command.trade)?;
+        self.settle_trade(org, command.trade, event).await
+    }
+
+    pub async fn approve_trade(&self, command: TradeCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let event = org.approve_trade(command.requesting_account, command.trade)?;
+        self.settle_trade(org, command.trade, event).await
+    }
+
+    pub async fn decline_trade(&self, command: TradeCommand) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.decline_trade(command.requesting_account, command.trade)?,
+            )
+            .await
+    }
+
+    pub async fn trades(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<Trade>, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        Ok(org.trades().to_vec())
+    }
+
+    //the last acceptance or approval completes the trade, the tasks change hands in the same write
+    async fn settle_trade(
+        &self,
+        org: Organization,
+        id: TradeId,
+        event: OrganizationEvent,
+    ) -> Result<(), anyhow::Error> {
+        let organization = *org.id();
+        let org = org.apply(&event);
+        if !org.trade_ready(id) {
+            return self.org_repo.handle(organization, event).await;
+        }
+        let Some(trade) = org.trades().iter().find(|trade| trade.id == id) else {
+            return Err(OrganizationError::TradeDoesNotExist.into());
+        };
+        let offered = self.task_repo.find_task_by_id(trade.offered).await?;
+        let requested = match trade.requested {
+            Some(task) => Some(self.task_repo.find_task_by_id(task).await?),
+            None => None,
+        };
+        let (completed, task_events) = org.complete_trade(id, &offered, requested.as_ref())?;
+        self.org_repo
+            .handle_assignment(organization, vec![event, completed], task_events)
+            .await
+    }
+
+    pub async fn fall_back_unclaimed_tasks(&self) -> Result<FallBacks, anyhow::Error> {
+        let now = Utc::now();
+        let mut out = FallBacks::default();
+        for task in self.task_repo.query_for_unclaimed_tasks(now).await? {
+            let org = self.org_repo.find_org_by_id(task.organization()).await?;
+            let (task_events, org_events) = match org.fall_back(&task, now, &mut rand::rng()) {
+                Ok(events) => events,
+                Err(_) => {
+                    out.failed += 1;
+                    continue;
+                }
+            };
+            //a claim that got in first makes the whole fallback fail
+            match self
+                .org_repo
+                .handle_assignment(task.organization(), org_events, task_events)
+                .await
+            {
+                Ok(()) => out.tasks += 1,
+                Err(_) => out.failed += 1,
+            }
+        }
+        Ok(out)
+    }
+
+    //a task that fails is left as it was and picked up again by the next run
+    pub async fn expire_tasks(&self) -> Result<Expiries, anyhow::Error> {
+        let mut out = Expiries::default();
+        for task in self.task_repo.query_for_expired_tasks().await? {
+            let expired = match task.expire() {
+                //nobody was assigned an open task, so there is nobody to reassign it from
+                Ok(event) if !matches!(task.status(), TaskStatus::Pending) => {
+                    self.task_repo.handle(event).await
+                }
+                Ok(event) => self.reassign(&task, event).await,
+                Err(error) => Err(error.into()),
+            };
+            match expired {
+                Ok(()) => out.tasks += 1,
+                Err(_) => out.failed += 1,
+            }
+        }
+        Ok(out)
+    }
+
+    pub async fn archive_organization(
+        &self,
+        command: ArchiveOrganizationCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let event = match command.archive {
+            true => org.archive(command.requesting_account, Utc::now())?,
+            false => org.unarchive(command.requesting_account)?,
+        };
+        self.org_repo.handle(command.organization, event).await?;
+        Ok(())
+    }
+
+    pub async fn change_settings(
+        &self,
+        command: ChangeSettingsCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.change_settings(command.requesting_account, command.settings)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn settings(
+        &self,
+        organization: OrganizationId,
+        requesting_account: AccountId,
+    ) -> Result<OrganizationSettings, anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(organization).await?;
+        org.authorize_member(requesting_account)?;
+        Ok(org.settings().clone())
+    }
+
+    pub async fn set_reassignment_policy(
+        &self,
+        command: SetReassignmentPolicyCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.set_reassignment_policy(command.requesting_account, command.policy)?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_repeating_task(
+        &self,
+        command: AddRepeatingTaskCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.add_repeating_task(
+                    command.requesting_account,
+                    command.assigned_to,
+                    command.tasks,
+                    command.period_days,
+                    command.starts,
+                )?,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn transfer_ownership(
+        &self,
+        command: TransferOwnershipCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        self.org_repo
+            .handle(
+                command.organization,
+                org.transfer_ownership(command.requesting_account, command.new_owner)?,
+            )
             .await?;
+
+        self.notifier
+            .notify(
+                command.new_owner,
+                Notification::OwnershipOffered {
+                    organization: command.organization,
+                    name: org.name().to_string(),
+                    from: command.requesting_account,
+                },
+            )
+            .await
+    }
+
+    pub async fn respond_to_ownership(
+        &self,
+        command: RespondToOwnershipCommand,
+    ) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(command.organization).await?;
+        let owner = org
+            .owner()
+            .ok_or(OrganizationError::InvalidOwnershipTransfer)?;
+        let event = match command.accept {
+            true => org.accept_ownership(command.requesting_account)?,
+            false => org.decline_ownership(command.requesting_account)?,
+        };
+        self.org_repo.handle(command.organization, event).await?;
+
+        self.notifier
+            .notify(
+                owner,
+                Notification::OwnershipAnswered {
+                    organization: command.organization,
+                    name: org.name().to_string(),
+                    to: command.requesting_account,
+                    accepted: command.accept,
+                },
+            )
+            .await
+    }
+
+    //a repeat that cannot be assigned stays due and is retried on the next run
+    pub async fn assign_repeating_tasks(&self) -> Result<RepeatsAssigned, anyhow::Error> {
+        let now = Utc::now();
+        let mut out = RepeatsAssigned::default();
+        for org in self.org_repo.query_for_pending_task_repeats().await {
+            for id in org.due_repeats(now) {
+                let assignment = match org.assign_repeating_task(id, now, &mut rand::rng()) {
+                    Ok(assignment) => assignment,
+                    Err(_) => {
+                        out.failed += 1;
+                        continue;
+                    }
+                };
+                //the repeat is only marked as assigned together with its tasks
+                match self
+                    .org_repo
+                    .handle_assignment(
+                        *org.id(),
+                        assignment.events,
+                        assignment.tasks.iter().map(|task| task.create()).collect(),
+                    )
+                    .await
+                {
+                    Ok(()) => {
+                        out.repeats += 1;
+                        out.tasks += assignment.tasks.len();
+                    }
+                    Err(_) => out.failed += 1,
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    //an account whose digest cannot be delivered is counted and skipped
+    pub async fn send_digests(
+        &self,
+        command: SendDigestsCommand,
+    ) -> Result<Digests, anyhow::Error> {
+        let mut by_account = BTreeMap::new();
+        let mut settings: HashMap<OrganizationId, OrganizationSettings> = HashMap::new();
+        for task in self.task_repo.query_for_pending_tasks().await? {
+            let org_settings = match settings.entry(task.organization()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let org = self.org_repo.find_org_by_id(task.organization()).await?;
+                    entry.insert(org.settings().clone())
+                }
+            };
+            by_account
+                .entry(task.assigned_to())
+                .or_insert_with(Vec::new)
+                .push(DigestEntry {
+                    task: task.id(),
+                    catalogue_task: task.catalogue_id(),
+                    expires: task.expires(),
+                    time_zone: org_settings.time_zone,
+                    locale: org_settings.locale(),
+                });
+        }
+
+        let mut out = Digests::default();
+        for (account, tasks) in by_account {
+            let last_week = command
+                .last_week
+                .iter()
+                .filter(|stats| stats.account == account)
+                .cloned()
+                .collect();
+            match self
+                .notifier
+                .notify(account, Notification::Digest { tasks, last_week })
+                .await
+            {
+                Ok(()) => out.sent += 1,
+                Err(_) => out.failed += 1,
+            }
+        }
+        Ok(out)
+    }
+
+    //tasks of an archived organization are frozen along with it
+    async fn ensure_active(&self, task: &TaskInstance) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(task.organization()).await?;
+        if org.archived().is_some() {
+            return Err(OrganizationError::Archived.into());
+        }
         Ok(())
     }
+
+    //the rejection or expiry is written together with the tasks replacing it, so neither happens without the other
+    async fn reassign(&self, task: &TaskInstance, event: TaskEvent) -> Result<(), anyhow::Error> {
+        let org = self.org_repo.find_org_by_id(task.organization()).await?;
+        let task = task.clone().apply(&event);
+        let Some(reassigned) = org.reassign(&task, Utc::now(), &mut rand::rng())? else {
+            return self.task_repo.handle(event).await;
+        };
+        self.org_repo
+            .handle_assignment(
+                task.organization(),
+                reassigned.events,
+                std::iter::once(event)
+                    .chain(reassigned.tasks.iter().map(|task| task.create()))
+                    .collect(),
+            )
+            .await?;
+
+        self.notifier
+            .notify(
+                task.assigned_by(),
+                Notification::TaskReassigned {
+                    task: task.id(),
+                    catalogue_task: task.catalogue_id(),
+                    status: task.status().clone(),
+                    previous: task.assigned_to(),
+                    reassigned_to: reassigned
+                        .tasks
+                        .iter()
+                        .map(|task| task.assigned_to())
+                        .collect(),
+                },
+            )
+            .await
+    }
+
+    pub async fn send_reminders(
+        &self,
+        command: SendRemindersCommand,
+    ) -> Result<Reminders, anyhow::Error> {
+        let now = Utc::now();
+        let mut thresholds = command.thresholds;
+        thresholds.sort();
+        let mut out = Reminders::default();
+        let Some(&longest) = thresholds.last() else {
+            return Ok(out);
+        };
+
+        for task in self
+            .task_repo
+            .query_for_tasks_expiring_before(now + longest)
+            .await?
+        {
+            match self.remind(&task, now, &thresholds).await {
+                Ok(true) => out.sent += 1,
+                Ok(false) => {}
+                Err(_) => out.failed += 1,
+            }
+        }
+
+        Ok(out)
+    }
+
+    //false when the task has no reminder due or it was already sent
+    async fn remind(
+        &self,
+        task: &TaskInstance,
+        now: DateTime<Utc>,
+        thresholds: &[Duration],
+    ) -> Result<bool, anyhow::Error> {
+        let crossed = task.crossed_reminder_thresholds(now, thresholds);
+        let Some((&tightest, wider)) = crossed.split_first() else {
+            return Ok(false);
+        };
+        if !self.task_repo.record_reminder(task.id(), tightest).await? {
+            return Ok(false);
+        }
+        //a task assigned close to its deadline only gets the tightest reminder
+        for &threshold in wider {
+            self.task_repo.record_reminder(task.id(), threshold).await?;
+        }
+
+        let notification = Notification::TaskExpiring {
+            task: task.id(),
+            catalogue_task: task.catalogue_id(),
+            expires: task.expires().unwrap_or(now),
+            threshold: tightest,
+        };
+        //forgetting the reminder lets the next run try again
+        if let Err(error) = self.notifier.notify(task.assigned_to(), notification).await {
+            self.task_repo.forget_reminder(task.id(), tightest).await?;
+            return Err(error);
+        }
+        Ok(true)
+    }
 }
 
 #[derive(Error, Debug)]
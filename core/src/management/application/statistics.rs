@@ -0,0 +1,84 @@
+use chrono::{DateTime, Days, NaiveDate, Utc};
+
+use crate::{
+    jobs::{JobCounts, JobFailure},
+    management::models::{
+        organization::OrganizationId,
+        statistics::{week_start, weekly_stats, WeeklyStats},
+    },
+};
+
+use super::ports::StatisticsRepository;
+
+#[derive(Debug, Clone)]
+pub struct StatisticsService<S>
+where
+    S: StatisticsRepository,
+{
+    repo: S,
+}
+
+impl<S> StatisticsService<S>
+where
+    S: StatisticsRepository,
+{
+    pub fn new(repo: S) -> Self {
+        Self { repo }
+    }
+
+    //aggregates every finished week that has not been aggregated yet, starting at the first task event
+    //weeks are aggregated in order, so a failed week stops the run and keeps the weeks before it
+    pub async fn compute_weekly_stats(&self, now: DateTime<Utc>) -> Result<usize, JobFailure> {
+        let last_finished = Self::last_finished_week(now);
+        let mut week = match self.repo.query_for_last_computed_week().await? {
+            Some(computed) => computed + Days::new(7),
+            None => match self.repo.query_for_first_task_event().await? {
+                Some(first) => week_start(first.date_naive()),
+                None => return Ok(0),
+            },
+        };
+
+        let mut computed = 0;
+        while week <= last_finished {
+            if let Err(error) = self.compute_week(week).await {
+                return Err(JobFailure {
+                    counts: JobCounts {
+                        processed: computed,
+                        failed: 1,
+                    },
+                    error,
+                });
+            }
+            week = week + Days::new(7);
+            computed += 1;
+        }
+        Ok(computed)
+    }
+
+    async fn compute_week(&self, week: NaiveDate) -> Result<(), anyhow::Error> {
+        let streams = self.repo.query_for_task_streams(week).await?;
+        self.repo
+            .save_week(week, weekly_stats(week, &streams))
+            .await
+    }
+
+    pub async fn last_week(&self, now: DateTime<Utc>) -> Result<Vec<WeeklyStats>, anyhow::Error> {
+        self.repo
+            .query_for_week(Self::last_finished_week(now))
+            .await
+    }
+
+    pub async fn organization_stats(
+        &self,
+        organization: OrganizationId,
+        since: NaiveDate,
+    ) -> Result<Vec<WeeklyStats>, anyhow::Error> {
+        self.repo
+            .query_for_organization(organization, week_start(since))
+            .await
+    }
+
+    fn last_finished_week(now: DateTime<Utc>) -> NaiveDate {
+        week_start(now.date_naive()) - Days::new(7)
+    }
+}
@@ -1 +1,110 @@
+use std::collections::HashSet;
 
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    catalogue::CatalogueTaskId,
+    management::models::{
+        eligibility::Ineligibility,
+        events::OrganizationEvent,
+        organization::{AccountType, OrganizationId, TagId},
+        task::{TaskId, TaskInstance},
+    },
+    shared::account::AccountId,
+};
+
+//outcome of one pass over all due repeating tasks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepeatsAssigned {
+    pub repeats: usize,
+    pub tasks: usize,
+    pub failed: usize,
+}
+
+//who would get what, and who was left out because they do not meet a task's requirements
+#[derive(Debug, Clone, Default)]
+pub struct AssignmentPreview {
+    pub tasks: Vec<TaskInstance>,
+    pub ineligible: Vec<Ineligibility>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Reminders {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Digests {
+    //accounts that got their digest
+    pub sent: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FallBacks {
+    //open tasks handed to their fallback assignment
+    pub tasks: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Expiries {
+    //pending and open tasks past their deadline
+    pub tasks: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Membership {
+    pub organization: OrganizationId,
+    pub account: AccountId,
+    pub account_type: AccountType,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub created: DateTime<Utc>,
+    pub event: OrganizationEvent,
+}
+
+//everything one account has going on, across all organizations it belongs to
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountOverview {
+    pub account: AccountId,
+    pub organizations: Vec<OrganizationMembership>,
+    //soonest deadline first
+    pub pending_tasks: Vec<PendingTask>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizationMembership {
+    pub organization: OrganizationId,
+    pub name: String,
+    pub account_type: AccountType,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingTask {
+    pub organization: OrganizationId,
+    pub task: TaskId,
+    pub catalogue_task: CatalogueTaskId,
+    pub assigned_by: AccountId,
+    pub expires: Option<DateTime<Utc>>,
+    pub tags: HashSet<TagId>,
+}
+
+impl From<&TaskInstance> for PendingTask {
+    fn from(task: &TaskInstance) -> Self {
+        PendingTask {
+            organization: task.organization(),
+            task: task.id(),
+            catalogue_task: task.catalogue_id(),
+            assigned_by: task.assigned_by(),
+            expires: task.expires(),
+            tags: task.tags().clone(),
+        }
+    }
+}
@@ -0,0 +1,940 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::{postgres::PgPoolOptions, types::Json, types::Uuid, Postgres, Transaction};
+
+use super::{
+    application::{
+        ports::{OrganizationRepository, StatisticsRepository, TaskRepository},
+        views::{AuditEntry, Membership},
+    },
+    models::{
+        events::{OrganizationEvent, TaskEvent},
+        organization::{
+            AccountType, Organization, OrganizationError, OrganizationId, TagId, TaskAssignmentType,
+        },
+        statistics::{week_bounds, TaskStream, WeeklyStats},
+        task::{ClaimDeadline, TaskDomainError, TaskId, TaskInstance, TaskStatus},
+        workload::{DayLoad, Workload},
+    },
+};
+use crate::{
+    catalogue::{Requirements, DEFAULT_EFFORT},
+    shared::account::AccountId,
+};
+
+#[derive(Debug, Clone)]
+pub struct PostgressTaskRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgressTaskRepository {
+    pub async fn new(path: &str) -> anyhow::Result<PostgressTaskRepository> {
+        let pool = PgPoolOptions::new()
+            .test_before_acquire(false)
+            .connect(path)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+struct TaskInstanceRecord {
+    id: Uuid,
+    organization: Uuid,
+    catalogue_task: Uuid,
+    assigned_to: Uuid,
+    assigned_by: Uuid,
+    expires: Option<DateTime<Utc>>,
+    status: String,
+    tags: Vec<Uuid>,
+    reassigned_from: Option<Uuid>,
+    reassignments: i32,
+    pooled: bool,
+    claim_deadline: Option<DateTime<Utc>>,
+    fallback: Option<Json<TaskAssignmentType>>,
+}
+
+impl TryFrom<TaskInstanceRecord> for TaskInstance {
+    type Error = anyhow::Error;
+
+    fn try_from(record: TaskInstanceRecord) -> Result<Self, Self::Error> {
+        Ok(TaskInstance::new(
+            record.id.into(),
+            record.organization.into(),
+            record.assigned_to.into(),
+            record.assigned_by.into(),
+            record.expires,
+            record.catalogue_task.into(),
+            TaskStatus::parse(&record.status)
+                .ok_or(anyhow!("unknown task status {}", record.status))?,
+        )?
+        .with_tags(record.tags.into_iter().map(TagId::from).collect())
+        .with_reassignment(
+            record.reassigned_from.map(TaskId::from),
+            record.reassignments.try_into()?,
+        )
+        .with_pool(
+            record.pooled,
+            record
+                .claim_deadline
+                .zip(record.fallback)
+                .map(|(at, fallback)| ClaimDeadline {
+                    at,
+                    fallback: fallback.0,
+                }),
+        ))
+    }
+}
+
+//appends the event to the task stream and folds it into the TASK_INSTANCE projection
+pub(crate) async fn append_task_event(
+    tx: &mut Transaction<'_, Postgres>,
+    event: &TaskEvent,
+    created: DateTime<Utc>,
+) -> Result<(), anyhow::Error> {
+    let id = event.task_id();
+    let current = sqlx::query_as!(
+        TaskInstanceRecord,
+        r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+        tags, reassigned_from, reassignments, pooled, claim_deadline,
+        fallback as "fallback: Json<TaskAssignmentType>"
+        FROM TASK_INSTANCE
+        WHERE id = $1
+        FOR UPDATE"#,
+        Uuid::from(id.ulid())
+    )
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    let current = match current {
+        Some(record) => TaskInstance::try_from(record)?,
+        None => match event {
+            TaskEvent::Assigned { .. } | TaskEvent::Posted { .. } => TaskInstance::default(),
+            _ => return Err(TaskDomainError::TaskDoesNotExist.into()),
+        },
+    };
+    current.verify(event)?;
+    let instance = current.apply(event);
+
+    sqlx::query!(
+        "INSERT INTO TASK_EVENT (task, organization, payload, created)
+        VALUES ($1, $2, $3, $4)",
+        Uuid::from(id.ulid()),
+        Uuid::from(instance.organization().ulid()),
+        Json(event) as _,
+        created
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO TASK_INSTANCE (id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline, fallback)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        ON CONFLICT (id) DO UPDATE SET
+            assigned_to = EXCLUDED.assigned_to,
+            assigned_by = EXCLUDED.assigned_by,
+            expires = EXCLUDED.expires,
+            status = EXCLUDED.status",
+        Uuid::from(instance.id().ulid()),
+        Uuid::from(instance.organization().ulid()),
+        Uuid::from(instance.catalogue_id().ulid()),
+        Uuid::from(instance.assigned_to().ulid()),
+        Uuid::from(instance.assigned_by().ulid()),
+        instance.expires(),
+        instance.status().as_str(),
+        &instance
+            .tags()
+            .iter()
+            .map(|tag| Uuid::from(tag.ulid()))
+            .collect::<Vec<Uuid>>(),
+        instance.reassigned_from().map(|task| Uuid::from(task.ulid())),
+        i32::try_from(instance.reassignments())?,
+        instance.pooled(),
+        instance.claim_deadline().map(|deadline| deadline.at),
+        instance
+            .claim_deadline()
+            .map(|deadline| Json(&deadline.fallback)) as _
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+//rewrites the ORGANIZATION_MEMBER, INVITATION and ARCHIVED_ORGANIZATION read models from the organization's events
+pub(crate) async fn project_organization(
+    tx: &mut Transaction<'_, Postgres>,
+    organization: OrganizationId,
+) -> Result<(), anyhow::Error> {
+    let records = sqlx::query!(
+        r#"SELECT payload as "payload: Json<OrganizationEvent>"
+        FROM ORGANIZATION_EVENT
+        WHERE organization = $1
+        ORDER BY sequence"#,
+        Uuid::from(organization.ulid())
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+    let org = records.iter().fold(Organization::default(), |org, record| {
+        org.apply(&record.payload)
+    });
+
+    sqlx::query!(
+        "DELETE FROM ORGANIZATION_MEMBER WHERE organization = $1",
+        Uuid::from(organization.ulid())
+    )
+    .execute(&mut **tx)
+    .await?;
+    for link in org.linked_accounts() {
+        sqlx::query!(
+            "INSERT INTO ORGANIZATION_MEMBER (organization, account, account_type)
+            VALUES ($1, $2, $3)",
+            Uuid::from(organization.ulid()),
+            Uuid::from(link.account().ulid()),
+            link.account_type().as_str()
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    sqlx::query!(
+        "DELETE FROM INVITATION WHERE organization = $1",
+        Uuid::from(organization.ulid())
+    )
+    .execute(&mut **tx)
+    .await?;
+    for invitation in org.invitations() {
+        sqlx::query!(
+            "INSERT INTO INVITATION (id, organization, code, account_type, max_uses, uses, expires, revoked)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            Uuid::from(invitation.id.ulid()),
+            Uuid::from(organization.ulid()),
+            invitation.code,
+            invitation.account_type.as_str(),
+            invitation.max_uses.map(i32::try_from).transpose()?,
+            i32::try_from(invitation.uses)?,
+            invitation.expires,
+            invitation.revoked
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    sqlx::query!(
+        "DELETE FROM ARCHIVED_ORGANIZATION WHERE organization = $1",
+        Uuid::from(organization.ulid())
+    )
+    .execute(&mut **tx)
+    .await?;
+    if let Some(archived) = org.archived() {
+        sqlx::query!(
+            "INSERT INTO ARCHIVED_ORGANIZATION (organization, archived) VALUES ($1, $2)",
+            Uuid::from(organization.ulid()),
+            archived
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+impl TaskRepository for PostgressTaskRepository {
+    async fn handle(&self, event: TaskEvent) -> Result<(), anyhow::Error> {
+        self.handle_many(vec![event]).await
+    }
+
+    async fn handle_many(&self, events: Vec<TaskEvent>) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+        for event in &events {
+            append_task_event(&mut tx, event, Utc::now()).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    fn publish(&self, _event: TaskEvent) -> Result<(), anyhow::Error> {
+        //no subscribers yet, handle already persists every event
+        Ok(())
+    }
+
+    async fn query_for_expired_tasks(&self) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+        fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status IN ('pending', 'open') AND expires < now()
+            AND organization NOT IN (SELECT organization FROM ARCHIVED_ORGANIZATION)"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn find_task_by_id(&self, id: TaskId) -> Result<TaskInstance, anyhow::Error> {
+        let records = sqlx::query!(
+            r#"SELECT payload as "payload: Json<TaskEvent>"
+            FROM TASK_EVENT
+            WHERE task = $1
+            ORDER BY sequence"#,
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if records.is_empty() {
+            return Err(TaskDomainError::TaskDoesNotExist.into());
+        }
+
+        Ok(records
+            .iter()
+            .fold(TaskInstance::default(), |task, record| {
+                task.apply(&record.payload)
+            }))
+    }
+
+    async fn query_for_pending_tasks(&self) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+        fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status = 'pending'
+            AND organization NOT IN (SELECT organization FROM ARCHIVED_ORGANIZATION)
+            ORDER BY expires NULLS LAST"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn query_for_pending_tasks_of(
+        &self,
+        account: AccountId,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+        fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status = 'pending' AND assigned_to = $1
+            ORDER BY expires NULLS LAST"#,
+            Uuid::from(account.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn query_for_tasks_expiring_before(
+        &self,
+        deadline: DateTime<Utc>,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+        fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status = 'pending' AND expires > now() AND expires <= $1
+            AND organization NOT IN (SELECT organization FROM ARCHIVED_ORGANIZATION)"#,
+            deadline
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn query_for_open_tasks(
+        &self,
+        organization: OrganizationId,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+            fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status = 'open' AND organization = $1
+            ORDER BY expires NULLS LAST"#,
+            Uuid::from(organization.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn query_for_unclaimed_tasks(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<TaskInstance>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            TaskInstanceRecord,
+            r#"SELECT id, organization, catalogue_task, assigned_to, assigned_by, expires, status,
+            tags, reassigned_from, reassignments, pooled, claim_deadline,
+            fallback as "fallback: Json<TaskAssignmentType>"
+            FROM TASK_INSTANCE
+            WHERE status = 'open' AND claim_deadline < $1
+            AND organization NOT IN (SELECT organization FROM ARCHIVED_ORGANIZATION)"#,
+            before
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(TaskInstance::try_from).collect()
+    }
+
+    async fn record_reminder(
+        &self,
+        task: TaskId,
+        threshold: Duration,
+    ) -> Result<bool, anyhow::Error> {
+        let result = sqlx::query!(
+            "INSERT INTO TASK_REMINDER (task, threshold_seconds)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING",
+            Uuid::from(task.ulid()),
+            threshold.num_seconds()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn forget_reminder(
+        &self,
+        task: TaskId,
+        threshold: Duration,
+    ) -> Result<(), anyhow::Error> {
+        sqlx::query!(
+            "DELETE FROM TASK_REMINDER
+            WHERE task = $1 AND threshold_seconds = $2",
+            Uuid::from(task.ulid()),
+            threshold.num_seconds()
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgressOrganizationRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgressOrganizationRepository {
+    pub async fn new(path: &str) -> anyhow::Result<PostgressOrganizationRepository> {
+        let pool = PgPoolOptions::new()
+            .test_before_acquire(false)
+            .connect(path)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+impl OrganizationRepository for PostgressOrganizationRepository {
+    async fn handle(
+        &self,
+        organization: OrganizationId,
+        event: OrganizationEvent,
+    ) -> Result<(), anyhow::Error> {
+        self.handle_many(organization, vec![event]).await
+    }
+
+    async fn handle_many(
+        &self,
+        organization: OrganizationId,
+        events: Vec<OrganizationEvent>,
+    ) -> Result<(), anyhow::Error> {
+        self.handle_assignment(organization, events, Vec::new())
+            .await
+    }
+
+    async fn handle_assignment(
+        &self,
+        organization: OrganizationId,
+        events: Vec<OrganizationEvent>,
+        tasks: Vec<TaskEvent>,
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+        //writes to one organization are serialized so the projection sees every committed event
+        sqlx::query!(
+            "SELECT pg_advisory_xact_lock(hashtext('jira-for-chores.organization.' || $1))",
+            organization.ulid().to_string()
+        )
+        .execute(&mut *tx)
+        .await?;
+        //the invitation was checked on a snapshot taken before the lock, it may have been used up since
+        for event in &events {
+            if let OrganizationEvent::InvitationRedeemed { id, .. } = event {
+                let redeemed = sqlx::query!(
+                    "UPDATE INVITATION SET uses = uses + 1
+                    WHERE id = $1 AND NOT revoked
+                    AND (max_uses IS NULL OR uses < max_uses)
+                    AND (expires IS NULL OR expires > now())",
+                    Uuid::from(id.ulid())
+                )
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+                if redeemed == 0 {
+                    return Err(OrganizationError::InvitationUnavailable.into());
+                }
+            }
+        }
+        for event in &events {
+            sqlx::query!(
+                "INSERT INTO ORGANIZATION_EVENT (organization, payload)
+                VALUES ($1, $2)",
+                Uuid::from(organization.ulid()),
+                Json(event) as _
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        for event in &tasks {
+            append_task_event(&mut tx, event, Utc::now()).await?;
+        }
+        project_organization(&mut tx, organization).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    fn publish(&self, _event: OrganizationEvent) {
+        //no subscribers yet, handle already persists every event
+    }
+
+    async fn query_for_pending_task_repeats(&self) -> Vec<Organization> {
+        let ids = sqlx::query_scalar!("SELECT DISTINCT organization FROM ORGANIZATION_EVENT")
+            .fetch_all(&self.pool)
+            .await
+            .unwrap_or_default();
+
+        let now = Utc::now();
+        let mut out = Vec::new();
+        for id in ids {
+            if let Ok(org) = self.find_org_by_id(id.into()).await {
+                if org.has_due_repeats(now) {
+                    out.push(org);
+                }
+            }
+        }
+        out
+    }
+
+    async fn find_org_by_id(&self, id: OrganizationId) -> Result<Organization, anyhow::Error> {
+        let records = sqlx::query!(
+            r#"SELECT payload as "payload: Json<OrganizationEvent>"
+            FROM ORGANIZATION_EVENT
+            WHERE organization = $1
+            ORDER BY sequence"#,
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        if records.is_empty() {
+            return Err(OrganizationError::OrganizationDoesNotExist.into());
+        }
+
+        let org = records.iter().fold(Organization::default(), |org, record| {
+            org.apply(&record.payload)
+        });
+
+        let pending = sqlx::query!(
+            "SELECT id, assigned_to
+            FROM TASK_INSTANCE
+            WHERE organization = $1 AND status = 'pending'",
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let org = pending.into_iter().fold(org, |org, record| {
+            org.with_pending_task(record.assigned_to.into(), record.id.into())
+        });
+
+        //tasks that were rejected, expired or cancelled do not count towards effort,
+        //claimed and traded tasks count for whoever has them now like assigned ones
+        let effort = sqlx::query!(
+            r#"SELECT task.assigned_to, SUM(catalogue.effort) as "effort!"
+            FROM TASK_INSTANCE task
+            JOIN CATALOGUE_TASK catalogue ON catalogue.id = task.catalogue_task
+            WHERE task.organization = $1
+            AND task.status IN ('pending', 'finished')
+            AND EXISTS (
+                SELECT 1 FROM TASK_EVENT event
+                WHERE event.task = task.id
+                AND event.payload ?| array['Assigned', 'Claimed', 'HandedOver']
+                AND event.created >= $2
+            )
+            GROUP BY task.assigned_to"#,
+            Uuid::from(id.ulid()),
+            org.settings().effort_window_start(Utc::now())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        //counted on the local day a task is due, tasks without a deadline on the day they last changed hands
+        let now = Utc::now();
+        let settings = org.settings();
+        let workload = sqlx::query!(
+            r#"SELECT task.assigned_to,
+                (COALESCE(task.expires - interval '1 microsecond', given.at) AT TIME ZONE $2)::date as "due!",
+                COUNT(*) as "tasks!",
+                SUM(catalogue.effort) as "effort!"
+            FROM TASK_INSTANCE task
+            JOIN CATALOGUE_TASK catalogue ON catalogue.id = task.catalogue_task
+            JOIN LATERAL (
+                SELECT MAX(event.created) as at FROM TASK_EVENT event
+                WHERE event.task = task.id AND event.payload ?| array['Assigned', 'Claimed', 'HandedOver']
+            ) given ON true
+            WHERE task.organization = $1
+            AND task.status IN ('pending', 'finished')
+            AND (COALESCE(task.expires - interval '1 microsecond', given.at) AT TIME ZONE $2)::date >= $3
+            GROUP BY task.assigned_to, 2"#,
+            Uuid::from(id.ulid()),
+            settings.time_zone.name(),
+            settings.week_start_of(now)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let catalogue = sqlx::query!(
+            "SELECT id, effort, min_age, skills FROM CATALOGUE_TASK WHERE organization = $1",
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let org = effort.into_iter().fold(org, |org, record| {
+            org.with_effort(
+                record.assigned_to.into(),
+                record.effort.try_into().unwrap_or(u32::MAX),
+            )
+        });
+
+        let mut workloads: HashMap<AccountId, Workload> = HashMap::new();
+        for record in workload {
+            workloads
+                .entry(record.assigned_to.into())
+                .or_default()
+                .days
+                .insert(
+                    record.due,
+                    DayLoad {
+                        tasks: record.tasks.try_into().unwrap_or(u32::MAX),
+                        effort: record.effort.try_into().unwrap_or(u32::MAX),
+                    },
+                );
+        }
+        let org = workloads.into_iter().fold(org, |org, (account, workload)| {
+            org.with_workload(account, workload)
+        });
+
+        Ok(catalogue.into_iter().fold(org, |org, record| {
+            org.with_catalogue_effort(
+                record.id.into(),
+                record.effort.try_into().unwrap_or(DEFAULT_EFFORT),
+            )
+            .with_catalogue_requirements(
+                record.id.into(),
+                Requirements::new(
+                    record.min_age.and_then(|age| age.try_into().ok()),
+                    record.skills,
+                ),
+            )
+        }))
+    }
+
+    async fn query_for_history(
+        &self,
+        id: OrganizationId,
+    ) -> Result<Vec<AuditEntry>, anyhow::Error> {
+        let records = sqlx::query!(
+            r#"SELECT payload as "payload: Json<OrganizationEvent>", created
+            FROM ORGANIZATION_EVENT
+            WHERE organization = $1
+            ORDER BY sequence"#,
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| AuditEntry {
+                created: record.created,
+                event: record.payload.0,
+            })
+            .collect())
+    }
+
+    async fn find_org_by_invitation(
+        &self,
+        code: &str,
+    ) -> Result<Option<OrganizationId>, anyhow::Error> {
+        Ok(
+            sqlx::query_scalar!("SELECT organization FROM INVITATION WHERE code = $1", code)
+                .fetch_optional(&self.pool)
+                .await?
+                .map(OrganizationId::from),
+        )
+    }
+
+    async fn query_for_members(
+        &self,
+        id: OrganizationId,
+    ) -> Result<Vec<Membership>, anyhow::Error> {
+        let records = sqlx::query!(
+            "SELECT organization, account, account_type
+            FROM ORGANIZATION_MEMBER
+            WHERE organization = $1
+            ORDER BY account",
+            Uuid::from(id.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records
+            .into_iter()
+            .map(|record| membership(record.organization, record.account, &record.account_type))
+            .collect()
+    }
+
+    async fn query_for_memberships(
+        &self,
+        account: AccountId,
+    ) -> Result<Vec<Membership>, anyhow::Error> {
+        let records = sqlx::query!(
+            "SELECT organization, account, account_type
+            FROM ORGANIZATION_MEMBER
+            WHERE account = $1
+            ORDER BY organization",
+            Uuid::from(account.ulid())
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records
+            .into_iter()
+            .map(|record| membership(record.organization, record.account, &record.account_type))
+            .collect()
+    }
+}
+
+fn membership(
+    organization: Uuid,
+    account: Uuid,
+    account_type: &str,
+) -> Result<Membership, anyhow::Error> {
+    Ok(Membership {
+        organization: organization.into(),
+        account: account.into(),
+        account_type: AccountType::parse(account_type)
+            .ok_or(anyhow!("unknown account type {}", account_type))?,
+    })
+}
+
+impl PostgressOrganizationRepository {
+    //fills the read models for organizations created before they existed
+    pub async fn rebuild_projections(&self) -> Result<usize, anyhow::Error> {
+        let ids = sqlx::query_scalar!("SELECT DISTINCT organization FROM ORGANIZATION_EVENT")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut tx = self.pool.begin().await?;
+        for &id in &ids {
+            project_organization(&mut tx, id.into()).await?;
+        }
+        tx.commit().await?;
+        Ok(ids.len())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostgressStatisticsRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgressStatisticsRepository {
+    pub async fn new(path: &str) -> anyhow::Result<PostgressStatisticsRepository> {
+        let pool = PgPoolOptions::new()
+            .test_before_acquire(false)
+            .connect(path)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+struct WeeklyStatsRecord {
+    organization: Uuid,
+    account: Uuid,
+    week_start: NaiveDate,
+    assigned: i32,
+    finished: i32,
+    rejected: i32,
+    expired: i32,
+}
+
+impl TryFrom<WeeklyStatsRecord> for WeeklyStats {
+    type Error = anyhow::Error;
+
+    fn try_from(record: WeeklyStatsRecord) -> Result<Self, Self::Error> {
+        Ok(WeeklyStats {
+            organization: record.organization.into(),
+            account: record.account.into(),
+            week_start: record.week_start,
+            assigned: record.assigned.try_into()?,
+            finished: record.finished.try_into()?,
+            rejected: record.rejected.try_into()?,
+            expired: record.expired.try_into()?,
+        })
+    }
+}
+
+impl StatisticsRepository for PostgressStatisticsRepository {
+    async fn query_for_first_task_event(&self) -> Result<Option<DateTime<Utc>>, anyhow::Error> {
+        Ok(sqlx::query_scalar!("SELECT min(created) FROM TASK_EVENT")
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    async fn query_for_last_computed_week(&self) -> Result<Option<NaiveDate>, anyhow::Error> {
+        Ok(
+            sqlx::query_scalar!("SELECT max(week_start) FROM STATS_WEEK")
+                .fetch_one(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn query_for_task_streams(
+        &self,
+        week_start: NaiveDate,
+    ) -> Result<Vec<TaskStream>, anyhow::Error> {
+        let (start, end) = week_bounds(week_start);
+        let records = sqlx::query!(
+            r#"SELECT task, payload as "payload: Json<TaskEvent>", created
+            FROM TASK_EVENT
+            WHERE created < $2 AND task IN (
+                SELECT task FROM TASK_EVENT WHERE created >= $1 AND created < $2
+            )
+            ORDER BY task, sequence"#,
+            start,
+            end
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut streams: Vec<TaskStream> = Vec::new();
+        let mut current = None;
+        for record in records {
+            if current != Some(record.task) {
+                current = Some(record.task);
+                streams.push(Vec::new());
+            }
+            if let Some(stream) = streams.last_mut() {
+                stream.push((record.created, record.payload.0));
+            }
+        }
+        Ok(streams)
+    }
+
+    async fn save_week(
+        &self,
+        week_start: NaiveDate,
+        stats: Vec<WeeklyStats>,
+    ) -> Result<(), anyhow::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("DELETE FROM WEEKLY_STATS WHERE week_start = $1", week_start)
+            .execute(&mut *tx)
+            .await?;
+
+        for entry in &stats {
+            sqlx::query!(
+                "INSERT INTO WEEKLY_STATS (organization, account, week_start, iso_year, iso_week,
+                    assigned, finished, rejected, expired)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+                Uuid::from(entry.organization.ulid()),
+                Uuid::from(entry.account.ulid()),
+                entry.week_start,
+                entry.iso_year(),
+                i32::try_from(entry.iso_week())?,
+                i32::try_from(entry.assigned)?,
+                i32::try_from(entry.finished)?,
+                i32::try_from(entry.rejected)?,
+                i32::try_from(entry.expired)?
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "INSERT INTO STATS_WEEK (week_start)
+            VALUES ($1)
+            ON CONFLICT (week_start) DO UPDATE SET computed = now()",
+            week_start
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn query_for_week(
+        &self,
+        week_start: NaiveDate,
+    ) -> Result<Vec<WeeklyStats>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            WeeklyStatsRecord,
+            "SELECT organization, account, week_start, assigned, finished, rejected, expired
+            FROM WEEKLY_STATS
+            WHERE week_start = $1",
+            week_start
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(WeeklyStats::try_from).collect()
+    }
+
+    async fn query_for_organization(
+        &self,
+        organization: OrganizationId,
+        since: NaiveDate,
+    ) -> Result<Vec<WeeklyStats>, anyhow::Error> {
+        let records = sqlx::query_as!(
+            WeeklyStatsRecord,
+            "SELECT organization, account, week_start, assigned, finished, rejected, expired
+            FROM WEEKLY_STATS
+            WHERE organization = $1 AND week_start >= $2
+            ORDER BY week_start, account",
+            Uuid::from(organization.ulid()),
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        records.into_iter().map(WeeklyStats::try_from).collect()
+    }
+}
@@ -0,0 +1,252 @@
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::shared::account::AccountId;
+
+use super::{organization::OrganizationError, settings::OrganizationSettings};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Default, Deserialize, Serialize)]
+pub struct UnavailabilityId(pub Ulid);
+
+impl UnavailabilityId {
+    pub fn new() -> UnavailabilityId {
+        UnavailabilityId(Ulid::new())
+    }
+
+    pub fn ulid(&self) -> Ulid {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Unavailability {
+    //a trip, summer camp or anything else with a start and an end
+    Away {
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    },
+    //every week on the same day in the organization's time zone, runs into the next day when until is not after from
+    Weekly {
+        weekday: Weekday,
+        from: NaiveTime,
+        until: NaiveTime,
+    },
+}
+
+impl Unavailability {
+    pub fn validate(&self) -> Result<(), OrganizationError> {
+        let valid = match self {
+            Unavailability::Away { from, until } => from < until,
+            Unavailability::Weekly { from, until, .. } => from != until,
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(OrganizationError::InvalidUnavailability),
+        }
+    }
+
+    //end of the occurrence the instant falls into, None when it falls into none
+    fn end_if_contains(
+        &self,
+        at: DateTime<Utc>,
+        settings: &OrganizationSettings,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            Unavailability::Away { from, until } => (*from <= at && at < *until).then_some(*until),
+            Unavailability::Weekly {
+                weekday,
+                from,
+                until,
+            } => {
+                let today = settings.local_date(at);
+                //an occurrence that started yesterday evening can still be running
+                [today.pred_opt(), Some(today)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|day| day.weekday() == *weekday)
+                    .find_map(|day| {
+                        let end_day = match until > from {
+                            true => day,
+                            false => day.succ_opt()?,
+                        };
+                        let start = settings.local_time(day, *from);
+                        let end = settings.local_time(end_day, *until);
+                        (start <= at && at < end).then_some(end)
+                    })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountUnavailability {
+    pub id: UnavailabilityId,
+    pub account: AccountId,
+    pub unavailability: Unavailability,
+}
+
+//whether the periods leave no time at all between from and until, without until only from itself is checked
+pub fn covers<'a>(
+    periods: impl Iterator<Item = &'a Unavailability> + Clone,
+    settings: &OrganizationSettings,
+    from: DateTime<Utc>,
+    until: Option<DateTime<Utc>>,
+) -> bool {
+    let until = until.unwrap_or(from).max(from);
+    let mut at = from;
+    loop {
+        let Some(end) = periods
+            .clone()
+            .filter_map(|period| period.end_if_contains(at, settings))
+            .max()
+        else {
+            return false;
+        };
+        if end >= until {
+            return true;
+        }
+        at = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Berlin;
+
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn away(from: &str, until: &str) -> Unavailability {
+        Unavailability::Away {
+            from: at(from),
+            until: at(until),
+        }
+    }
+
+    fn covered(periods: &[Unavailability], from: &str, until: Option<&str>) -> bool {
+        covers(
+            periods.iter(),
+            &OrganizationSettings::default(),
+            at(from),
+            until.map(at),
+        )
+    }
+
+    #[test]
+    fn nothing_covers_nothing() {
+        assert!(!covered(&[], "2024-01-03T12:00:00Z", None));
+        assert!(!covered(
+            &[],
+            "2024-01-03T12:00:00Z",
+            Some("2024-01-04T12:00:00Z")
+        ));
+    }
+
+    #[test]
+    fn away_ends_at_the_day_boundary() {
+        let periods = [away("2024-01-03T00:00:00Z", "2024-01-04T00:00:00Z")];
+        assert!(covered(&periods, "2024-01-03T00:00:00Z", None));
+        assert!(covered(
+            &periods,
+            "2024-01-03T00:00:00Z",
+            Some("2024-01-04T00:00:00Z")
+        ));
+        //the end itself is free again
+        assert!(!covered(&periods, "2024-01-04T00:00:00Z", None));
+        assert!(!covered(
+            &periods,
+            "2024-01-03T00:00:00Z",
+            Some("2024-01-04T00:00:01Z")
+        ));
+        assert!(!covered(&periods, "2024-01-02T23:59:59Z", None));
+    }
+
+    #[test]
+    fn periods_that_touch_cover_together() {
+        let periods = [
+            away("2024-01-03T00:00:00Z", "2024-01-04T00:00:00Z"),
+            away("2024-01-04T00:00:00Z", "2024-01-05T00:00:00Z"),
+        ];
+        assert!(covered(
+            &periods,
+            "2024-01-03T08:00:00Z",
+            Some("2024-01-04T20:00:00Z")
+        ));
+        let gap = [
+            away("2024-01-03T00:00:00Z", "2024-01-04T00:00:00Z"),
+            away("2024-01-04T00:00:01Z", "2024-01-05T00:00:00Z"),
+        ];
+        assert!(!covered(
+            &gap,
+            "2024-01-03T08:00:00Z",
+            Some("2024-01-04T20:00:00Z")
+        ));
+    }
+
+    #[test]
+    fn weekly_runs_into_the_next_day() {
+        //wednesday night until thursday morning
+        let periods = [Unavailability::Weekly {
+            weekday: Weekday::Wed,
+            from: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            until: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        }];
+        assert!(covered(&periods, "2024-01-03T22:00:00Z", None));
+        assert!(covered(&periods, "2024-01-04T00:00:00Z", None));
+        assert!(covered(
+            &periods,
+            "2024-01-03T23:00:00Z",
+            Some("2024-01-04T06:00:00Z")
+        ));
+        assert!(!covered(&periods, "2024-01-04T06:00:00Z", None));
+        assert!(!covered(&periods, "2024-01-04T22:00:00Z", None));
+    }
+
+    #[test]
+    fn weekly_follows_the_time_zone() {
+        let periods = [Unavailability::Weekly {
+            weekday: Weekday::Wed,
+            from: NaiveTime::MIN,
+            until: NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+        }];
+        let settings = OrganizationSettings {
+            time_zone: Berlin,
+            ..Default::default()
+        };
+        //midnight in berlin is 23:00 utc the day before in winter
+        assert!(covers(
+            periods.iter(),
+            &settings,
+            at("2024-01-02T23:00:00Z"),
+            None
+        ));
+        assert!(!covers(
+            periods.iter(),
+            &settings,
+            at("2024-01-03T11:00:00Z"),
+            None
+        ));
+    }
+
+    #[test]
+    fn validates_periods() {
+        assert!(away("2024-01-03T00:00:00Z", "2024-01-04T00:00:00Z")
+            .validate()
+            .is_ok());
+        assert!(away("2024-01-03T00:00:00Z", "2024-01-03T00:00:00Z")
+            .validate()
+            .is_err());
+        let noon = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        assert!(Unavailability::Weekly {
+            weekday: Weekday::Mon,
+            from: noon,
+            until: noon,
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use std::{collections::BTreeSet, fmt};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    catalogue::{CatalogueTaskId, Requirements},
+    shared::account::AccountId,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum IneligibleReason {
+    //nobody can tell whether the account is old enough
+    NoBirthdate { min_age: u32 },
+    TooYoung { min_age: u32, age: u32 },
+    MissingSkills { skills: BTreeSet<String> },
+}
+
+impl fmt::Display for IneligibleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IneligibleReason::NoBirthdate { min_age } => {
+                write!(f, "needs to be {min_age} but has no birthdate")
+            }
+            IneligibleReason::TooYoung { min_age, age } => {
+                write!(f, "needs to be {min_age} but is {age}")
+            }
+            IneligibleReason::MissingSkills { skills } => {
+                let skills: Vec<&str> = skills.iter().map(String::as_str).collect();
+                write!(f, "lacks {}", skills.join(", "))
+            }
+        }
+    }
+}
+
+//why an account was left out of an assignment of a catalogue task
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Ineligibility {
+    pub account: AccountId,
+    pub task: CatalogueTaskId,
+    pub reasons: Vec<IneligibleReason>,
+}
+
+impl fmt::Display for Ineligibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reasons: Vec<String> = self.reasons.iter().map(ToString::to_string).collect();
+        write!(
+            f,
+            "{} for {}: {}",
+            self.account.ulid(),
+            self.task.ulid(),
+            reasons.join(", ")
+        )
+    }
+}
+
+//everything the account falls short of, empty when it meets the requirements
+pub fn unmet(
+    requirements: &Requirements,
+    birthdate: Option<NaiveDate>,
+    skills: &BTreeSet<String>,
+    today: NaiveDate,
+) -> Vec<IneligibleReason> {
+    let mut reasons = Vec::new();
+    if let Some(min_age) = requirements.min_age {
+        match birthdate.map(|birthdate| today.years_since(birthdate).unwrap_or(0)) {
+            None => reasons.push(IneligibleReason::NoBirthdate { min_age }),
+            Some(age) if age < min_age => reasons.push(IneligibleReason::TooYoung { min_age, age }),
+            Some(_) => {}
+        }
+    }
+    let missing: BTreeSet<String> = requirements.skills.difference(skills).cloned().collect();
+    if !missing.is_empty() {
+        reasons.push(IneligibleReason::MissingSkills { skills: missing });
+    }
+    reasons
+}
+
+pub fn explain(ineligible: &[Ineligibility]) -> String {
+    let explanations: Vec<String> = ineligible.iter().map(ToString::to_string).collect();
+    explanations.join("; ")
+}
@@ -1,27 +1,81 @@
-use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeSet, HashSet};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::catalogue::CatalogueTaskId;
 use crate::shared::account::AccountId;
 
-use super::organization::{AccountType, OrganizationId, TagId};
+use super::availability::{Unavailability, UnavailabilityId};
+use super::invitation::InvitationId;
+use super::organization::{
+    AccountType, AssignmentType, OrganizationId, ReassignmentPolicy, RepeatingTaskId, TagId,
+};
+use super::policy::Capability;
+use super::rotation::RotationKey;
+use super::settings::OrganizationSettings;
 
-use super::task::TaskId;
+use super::task::{ClaimDeadline, TaskId};
+use super::trade::TradeId;
+use super::workload::{CapTarget, WorkloadCaps};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum TaskEvent {
     Assigned {
         id: TaskId,
+        organization: OrganizationId,
         assigned_to: AccountId,
         assigned_by: AccountId,
         task: CatalogueTaskId,
         expires: Option<DateTime<Utc>>,
+        #[serde(default)]
+        tags: HashSet<TagId>,
+        #[serde(default)]
+        reassigned_from: Option<TaskId>,
+        #[serde(default)]
+        reassignments: u32,
+        //set when the worker was picked at random, random_pick(candidates, seed) replays the pick
+        #[serde(default)]
+        seed: Option<u64>,
+        #[serde(default)]
+        candidates: Vec<AccountId>,
+    },
+    //an open task in the pool, see TaskInstance::post
+    Posted {
+        id: TaskId,
+        organization: OrganizationId,
+        posted_by: AccountId,
+        task: CatalogueTaskId,
+        expires: Option<DateTime<Utc>>,
+        tags: HashSet<TagId>,
+        claim_deadline: Option<ClaimDeadline>,
+    },
+    Claimed {
+        task_id: TaskId,
+        account: AccountId,
+    },
+    Unclaimed {
+        task_id: TaskId,
+        account: AccountId,
+    },
+    //followed by the Assigned events of the fallback assignment
+    ClaimDeadlinePassed {
+        task_id: TaskId,
+    },
+    //the task changes hands through a trade, see OrganizationEvent::TradeCompleted
+    HandedOver {
+        task_id: TaskId,
+        from: AccountId,
+        to: AccountId,
+        trade: TradeId,
     },
     Finished {
         task_id: TaskId,
     },
     TimeAdded {
         task_id: TaskId,
+        #[serde(with = "crate::shared::serde_duration::seconds")]
         duration: Duration,
     },
     Rejected {
@@ -32,9 +86,30 @@ pub enum TaskEvent {
         task_id: TaskId,
         assigned_by: AccountId,
     },
+    Cancelled {
+        task_id: TaskId,
+        cancelled_by: AccountId,
+    },
 }
 
-#[derive(Debug, Clone)]
+impl TaskEvent {
+    pub fn task_id(&self) -> TaskId {
+        match self {
+            TaskEvent::Assigned { id, .. } | TaskEvent::Posted { id, .. } => *id,
+            TaskEvent::Claimed { task_id, .. }
+            | TaskEvent::Unclaimed { task_id, .. }
+            | TaskEvent::ClaimDeadlinePassed { task_id }
+            | TaskEvent::HandedOver { task_id, .. }
+            | TaskEvent::Finished { task_id }
+            | TaskEvent::TimeAdded { task_id, .. }
+            | TaskEvent::Rejected { task_id, .. }
+            | TaskEvent::Expired { task_id, .. }
+            | TaskEvent::Cancelled { task_id, .. } => *task_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum OrganizationEvent {
     Created {
@@ -54,6 +129,22 @@ pub enum OrganizationEvent {
         tag_id: TagId,
         account: AccountId,
     },
+    TagRenamed {
+        tag_id: TagId,
+        name: String,
+    },
+    EditorRemovedFromTag {
+        tag_id: TagId,
+        account: AccountId,
+    },
+    WorkerRemovedFromTag {
+        tag_id: TagId,
+        account: AccountId,
+    },
+    TagParentSet {
+        tag_id: TagId,
+        parent: Option<TagId>,
+    },
     TagRemoverd {
         tag: TagId,
     },
@@ -61,4 +152,133 @@ pub enum OrganizationEvent {
         account: AccountId,
         account_type: AccountType,
     },
+    InvitationCreated {
+        id: InvitationId,
+        code: String,
+        account_type: AccountType,
+        tags: HashSet<TagId>,
+        max_uses: Option<u32>,
+        expires: Option<DateTime<Utc>>,
+        created_by: AccountId,
+    },
+    //followed by the AccountLinked and WorkerAddedToTag events of the redeeming account
+    InvitationRedeemed {
+        id: InvitationId,
+        account: AccountId,
+    },
+    InvitationRevoked {
+        id: InvitationId,
+        revoked_by: AccountId,
+    },
+    //replaces everything the role was granted before
+    RoleCapabilitiesSet {
+        account_type: AccountType,
+        capabilities: HashSet<Capability>,
+    },
+    RoleChanged {
+        account: AccountId,
+        from: AccountType,
+        to: AccountType,
+        changed_by: AccountId,
+    },
+    UnavailabilityAdded {
+        id: UnavailabilityId,
+        account: AccountId,
+        unavailability: Unavailability,
+        added_by: AccountId,
+    },
+    UnavailabilityRemoved {
+        id: UnavailabilityId,
+        removed_by: AccountId,
+    },
+    TradeProposed {
+        id: TradeId,
+        proposed_by: AccountId,
+        counterparty: AccountId,
+        offered: TaskId,
+        requested: Option<TaskId>,
+        approvers: BTreeSet<AccountId>,
+    },
+    TradeAccepted {
+        id: TradeId,
+    },
+    TradeApproved {
+        id: TradeId,
+        approved_by: AccountId,
+    },
+    //declined by the counterparty or an approver, or withdrawn by whoever proposed it
+    TradeDeclined {
+        id: TradeId,
+        declined_by: AccountId,
+    },
+    //written together with the HandedOver events of the traded tasks
+    TradeCompleted {
+        id: TradeId,
+    },
+    HandicapSet {
+        account: AccountId,
+        share: u32,
+        set_by: AccountId,
+    },
+    WorkloadCapsSet {
+        target: CapTarget,
+        caps: WorkloadCaps,
+        set_by: AccountId,
+    },
+    //replaces what was known about the account, catalogue requirements are checked against it
+    ProfileSet {
+        account: AccountId,
+        birthdate: Option<NaiveDate>,
+        skills: BTreeSet<String>,
+        set_by: AccountId,
+    },
+    //emitted after the account was removed from every tag
+    AccountUnlinked {
+        account: AccountId,
+    },
+    ReassignmentPolicySet {
+        policy: Option<ReassignmentPolicy>,
+    },
+    RotationAdvanced {
+        key: RotationKey,
+        last: AccountId,
+    },
+    Archived {
+        archived_by: AccountId,
+        at: DateTime<Utc>,
+    },
+    Unarchived {
+        unarchived_by: AccountId,
+    },
+    SettingsChanged {
+        settings: OrganizationSettings,
+        changed_by: AccountId,
+    },
+    RepeatingTaskAdded {
+        id: RepeatingTaskId,
+        requesting_account: AccountId,
+        assigned_to: AssignmentType,
+        tasks: Vec<CatalogueTaskId>,
+        period_days: u64,
+        starts: DateTime<Utc>,
+    },
+    RepeatingTaskAssigned {
+        id: RepeatingTaskId,
+        assigned: DateTime<Utc>,
+    },
+    RepeatingTaskRemoved {
+        id: RepeatingTaskId,
+    },
+    OwnershipOffered {
+        from: AccountId,
+        to: AccountId,
+    },
+    OwnershipDeclined {
+        account: AccountId,
+    },
+    //the new owner accepted, the previous owner is now an admin
+    OwnershipTransferred {
+        from: AccountId,
+        to: AccountId,
+    },
 }
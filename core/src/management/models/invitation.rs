@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Uuid;
+use ulid::Ulid;
+
+use crate::shared::account::AccountId;
+
+use super::organization::{AccountType, TagId};
+
+//no 0/O or 1/I/L so codes can be read out loud
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Default, Deserialize, Serialize)]
+pub struct InvitationId(pub Ulid);
+
+impl InvitationId {
+    pub fn new() -> InvitationId {
+        InvitationId(Ulid::new())
+    }
+
+    pub fn ulid(&self) -> Ulid {
+        self.0
+    }
+}
+
+impl From<Uuid> for InvitationId {
+    fn from(value: Uuid) -> Self {
+        Self(value.into())
+    }
+}
+
+pub fn new_code() -> String {
+    let mut rng = rand::rng();
+    (0..CODE_LENGTH)
+        .filter_map(|_| CODE_ALPHABET.choose(&mut rng).map(|&c| c as char))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Invitation {
+    pub id: InvitationId,
+    pub code: String,
+    pub account_type: AccountType,
+    //tags the redeeming account joins as a worker
+    pub tags: HashSet<TagId>,
+    //None for an invitation that can be used any number of times
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+    pub expires: Option<DateTime<Utc>>,
+    pub created_by: AccountId,
+    pub revoked: bool,
+}
+
+impl Invitation {
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        !self.revoked
+            && self.max_uses.is_none_or(|max_uses| self.uses < max_uses)
+            && self.expires.is_none_or(|expires| expires > now)
+    }
+}
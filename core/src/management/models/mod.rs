@@ -1,4 +1,14 @@
+pub mod availability;
 pub mod daily;
+pub mod eligibility;
 pub mod events;
+pub mod invitation;
 pub mod organization;
+pub mod planner;
+pub mod policy;
+pub mod rotation;
+pub mod settings;
+pub mod statistics;
 pub mod task;
+pub mod trade;
+pub mod workload;
@@ -1,20 +1,38 @@
-use std::{collections::HashSet, vec};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    vec,
+};
 
-use chrono::{DateTime, Days, Utc};
-use rand::seq::IndexedRandom;
+use chrono::{DateTime, Days, Duration, NaiveDate, Utc};
+use rand::{seq::IndexedRandom, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use sqlx::types::Uuid;
 use thiserror::Error;
 use ulid::Ulid;
 
-use crate::{catalogue::CatalogueTaskId, shared::account::AccountId};
+use crate::{
+    catalogue::{normalize_skills, CatalogueTaskId, Requirements, DEFAULT_EFFORT},
+    shared::account::AccountId,
+};
 
 use super::{
-    events::OrganizationEvent,
-    task::{TaskDomainError, TaskId, TaskInstance, TaskStatus::Pending},
+    availability::{covers, AccountUnavailability, Unavailability, UnavailabilityId},
+    eligibility::{explain, unmet, Ineligibility},
+    events::{OrganizationEvent, TaskEvent},
+    invitation::{new_code, Invitation, InvitationId},
+    planner::{plan, PlanItem, PlanWorker, Slot},
+    policy::{Capability, Policy, Scope},
+    rotation::{Rotation, RotationKey, Rotations},
+    settings::OrganizationSettings,
+    task::{ClaimDeadline, TaskDomainError, TaskId, TaskInstance, TaskStatus, TaskStatus::Pending},
+    trade::{Trade, TradeId},
+    workload::{CapTarget, DueDay, Workload, WorkloadCaps},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Default, Deserialize, Serialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize, Serialize,
+)]
 pub struct OrganizationId(pub Ulid);
 
 impl OrganizationId {
@@ -39,6 +57,25 @@ pub struct Organization {
     name: String,
     tags: Vec<Tag>,
     linked_accounts: Vec<AccountLink>,
+    reassignment_policy: Option<ReassignmentPolicy>,
+    repeating_tasks: Vec<RepeatingTask>,
+    //account the owner offered the organization to, waiting for an answer
+    ownership_offer: Option<AccountId>,
+    //pending tasks of accounts that are no longer linked
+    orphaned_tasks: Vec<TaskId>,
+    invitations: Vec<Invitation>,
+    policy: Policy,
+    settings: OrganizationSettings,
+    //set while the organization is archived, archived organizations only allow reads
+    archived: Option<DateTime<Utc>>,
+    rotations: Rotations,
+    //effort points of the catalogue, attached by repositories like pending tasks
+    catalogue_effort: HashMap<CatalogueTaskId, u32>,
+    catalogue_requirements: HashMap<CatalogueTaskId, Requirements>,
+    unavailability: Vec<AccountUnavailability>,
+    //proposed trades that were neither completed nor declined yet
+    trades: Vec<Trade>,
+    caps: HashMap<CapTarget, WorkloadCaps>,
 }
 
 impl Organization {
@@ -50,6 +87,84 @@ impl Organization {
         &self.name
     }
 
+    pub fn owner(&self) -> Option<AccountId> {
+        self.linked_accounts
+            .iter()
+            .find(|link| link.account_type == AccountType::Owner)
+            .map(|link| link.account)
+    }
+
+    pub fn ownership_offer(&self) -> Option<AccountId> {
+        self.ownership_offer
+    }
+
+    pub fn linked_accounts(&self) -> &[AccountLink] {
+        &self.linked_accounts
+    }
+
+    pub fn pending_tasks_of(&self, account: AccountId) -> Vec<TaskId> {
+        self.linked_accounts
+            .iter()
+            .find(|link| link.account == account)
+            .map(|link| link.tasks.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn orphaned_tasks(&self) -> &[TaskId] {
+        &self.orphaned_tasks
+    }
+
+    pub fn invitations(&self) -> &[Invitation] {
+        &self.invitations
+    }
+
+    pub fn settings(&self) -> &OrganizationSettings {
+        &self.settings
+    }
+
+    pub fn archived(&self) -> Option<DateTime<Utc>> {
+        self.archived
+    }
+
+    fn ensure_active(&self) -> Result<(), OrganizationError> {
+        match self.archived {
+            Some(_) => Err(OrganizationError::Archived),
+            None => Ok(()),
+        }
+    }
+
+    pub fn archive(
+        &self,
+        requesting_account: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        if self.owner() != Some(requesting_account) {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        self.ensure_active()?;
+
+        Ok(OrganizationEvent::Archived {
+            archived_by: requesting_account,
+            at: now,
+        })
+    }
+
+    pub fn unarchive(
+        &self,
+        requesting_account: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        if self.owner() != Some(requesting_account) {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        if self.archived.is_none() {
+            return Err(OrganizationError::NotArchived);
+        }
+
+        Ok(OrganizationEvent::Unarchived {
+            unarchived_by: requesting_account,
+        })
+    }
+
     pub fn new(
         id: OrganizationId,
         name: String,
@@ -61,6 +176,20 @@ impl Organization {
             name,
             tags,
             linked_accounts,
+            reassignment_policy: None,
+            repeating_tasks: Vec::new(),
+            ownership_offer: None,
+            orphaned_tasks: Vec::new(),
+            invitations: Vec::new(),
+            policy: Policy::default(),
+            settings: OrganizationSettings::default(),
+            archived: None,
+            rotations: Rotations::default(),
+            catalogue_effort: HashMap::new(),
+            catalogue_requirements: HashMap::new(),
+            unavailability: Vec::new(),
+            trades: Vec::new(),
+            caps: HashMap::new(),
         })
     }
 
@@ -69,16 +198,26 @@ impl Organization {
             id: OrganizationId::new(),
             name,
             tags: Vec::new(),
-            linked_accounts: vec![AccountLink {
-                account,
-                account_type: AccountType::Owner,
-                tasks: Vec::new(),
-            }],
+            linked_accounts: vec![AccountLink::new(account, AccountType::Owner, Vec::new())],
+            reassignment_policy: None,
+            repeating_tasks: Vec::new(),
+            ownership_offer: None,
+            orphaned_tasks: Vec::new(),
+            invitations: Vec::new(),
+            policy: Policy::default(),
+            settings: OrganizationSettings::default(),
+            archived: None,
+            rotations: Rotations::default(),
+            catalogue_effort: HashMap::new(),
+            catalogue_requirements: HashMap::new(),
+            unavailability: Vec::new(),
+            trades: Vec::new(),
+            caps: HashMap::new(),
         })
     }
 
-    pub fn into_create_event(&self) -> Result<Vec<OrganizationEvent>, OrganizationError> {
-        if self.linked_accounts.len() == 0 {
+    pub fn into_create_event(self) -> Result<Vec<OrganizationEvent>, OrganizationError> {
+        if self.linked_accounts.is_empty() {
             return Err(OrganizationError::CannotCreate);
         }
         let mut links: Vec<OrganizationEvent> = self
@@ -93,7 +232,7 @@ impl Organization {
             0..0,
             vec![OrganizationEvent::Created {
                 id: self.id,
-                name: self.name.clone(),
+                name: self.name,
             }],
         );
         Ok(links)
@@ -104,14 +243,19 @@ impl Organization {
         name: String,
         requesting_account: AccountId,
     ) -> Result<Vec<OrganizationEvent>, OrganizationError> {
-        self.linked_accounts
-            .iter()
-            .find(|link| link.account == requesting_account)
-            .ok_or(OrganizationError::NotAuthorized)?;
-        self.tags
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::TagCreate,
+            Scope::Organization,
+        )?;
+        if self
+            .tags
             .iter()
-            .find(|existing_tag| existing_tag.name == name)
-            .ok_or(OrganizationError::TagAlreadyExists)?;
+            .any(|existing_tag| existing_tag.name == name)
+        {
+            return Err(OrganizationError::TagAlreadyExists);
+        }
 
         let id = TagId::new();
         Ok(vec![
@@ -127,24 +271,157 @@ impl Organization {
         ])
     }
 
-    pub fn add_worker_to_tag(
+    pub fn rename_tag(
         &self,
         tag_id: TagId,
         requesting_account: AccountId,
-        worker: AccountId,
+        name: String,
     ) -> Result<OrganizationEvent, OrganizationError> {
-        let tag = self
+        self.authorize_tag_edit(tag_id, requesting_account)?;
+        if self
             .tags
             .iter()
-            .find(|&existing_tag| existing_tag.id == tag_id)
-            .ok_or(OrganizationError::TagDoesNotExist)?;
-        tag.authorized_editors
+            .any(|existing_tag| existing_tag.name == name && existing_tag.id != tag_id)
+        {
+            return Err(OrganizationError::TagAlreadyExists);
+        }
+
+        Ok(OrganizationEvent::TagRenamed { tag_id, name })
+    }
+
+    //repeating tasks assigned through the tag are removed with it, pending tasks keep their assignee
+    pub fn remove_tag(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+    ) -> Result<Vec<OrganizationEvent>, OrganizationError> {
+        self.authorize_tag_edit(tag_id, requesting_account)?;
+
+        let mut events: Vec<OrganizationEvent> = self
+            .repeating_tasks
+            .iter()
+            .filter(|repeat| match &repeat.assigned_to {
+                AssignmentType::Tags { tags, .. } => tags.contains(&tag_id),
+                AssignmentType::Account(_) => false,
+            })
+            .map(|repeat| OrganizationEvent::RepeatingTaskRemoved { id: repeat.id })
+            .collect();
+        //children move up to the removed tag's parent so they keep what they inherited from above it
+        let parent = self.tag(tag_id)?.parent;
+        events.extend(
+            self.tags
+                .iter()
+                .filter(|tag| tag.parent == Some(tag_id))
+                .map(|child| OrganizationEvent::TagParentSet {
+                    tag_id: child.id,
+                    parent,
+                }),
+        );
+        events.push(OrganizationEvent::TagRemoverd { tag: tag_id });
+        Ok(events)
+    }
+
+    //the tag inherits the workers and editors of its parent and all of the parent's ancestors
+    pub fn set_tag_parent(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+        parent: Option<TagId>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        //moving a tag needs control over both ends
+        let mut scope = HashSet::from([tag_id]);
+        scope.extend(parent);
+        self.authorize(
+            requesting_account,
+            Capability::TagManage,
+            Scope::Tags(&scope),
+        )?;
+        self.tag(tag_id)?;
+        if let Some(parent) = parent {
+            self.tag(parent)?;
+            if parent == tag_id || self.ancestors(parent).any(|ancestor| ancestor.id == tag_id) {
+                return Err(OrganizationError::TagCycle);
+            }
+        }
+
+        Ok(OrganizationEvent::TagParentSet { tag_id, parent })
+    }
+
+    fn tag(&self, tag_id: TagId) -> Result<&Tag, OrganizationError> {
+        self.tags
+            .iter()
+            .find(|tag| tag.id == tag_id)
+            .ok_or(OrganizationError::TagDoesNotExist)
+    }
+
+    //parent first, stops early should the events ever describe a cycle
+    fn ancestors(&self, tag_id: TagId) -> impl Iterator<Item = &Tag> {
+        let mut seen = HashSet::from([tag_id]);
+        let mut current = self.tag(tag_id).ok().and_then(|tag| tag.parent);
+        std::iter::from_fn(move || {
+            let parent = self.tag(current?).ok()?;
+            if !seen.insert(parent.id) {
+                return None;
+            }
+            current = parent.parent;
+            Some(parent)
+        })
+    }
+
+    //the tag and every tag above it
+    fn lineage<'a>(&'a self, tag: &'a Tag) -> impl Iterator<Item = &'a Tag> {
+        std::iter::once(tag).chain(self.ancestors(tag.id))
+    }
+
+    pub fn workers_of_tag(&self, tag_id: TagId) -> Result<HashSet<AccountId>, OrganizationError> {
+        Ok(self
+            .lineage(self.tag(tag_id)?)
+            .flat_map(|tag| tag.workers.iter().copied())
+            .collect())
+    }
+
+    pub fn editors_of_tag(&self, tag_id: TagId) -> Result<HashSet<AccountId>, OrganizationError> {
+        Ok(self
+            .lineage(self.tag(tag_id)?)
+            .flat_map(|tag| tag.authorized_editors.iter().copied())
+            .collect())
+    }
+
+    pub fn add_worker_to_tag(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+        worker: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.authorize_tag_edit(tag_id, requesting_account)?;
+        if !self
+            .linked_accounts
             .iter()
-            .find(|&&editor| editor == requesting_account)
-            .ok_or(OrganizationError::NotAuthorized)?;
+            .any(|link| link.account == worker)
+        {
+            return Err(OrganizationError::NotInOrg);
+        }
 
         Ok(OrganizationEvent::WorkerAddedToTag {
-            tag_id: tag_id,
+            tag_id,
+            account: worker,
+        })
+    }
+
+    pub fn remove_worker_from_tag(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+        worker: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        let tag = self.authorize_tag_edit(tag_id, requesting_account)?;
+        if !tag.workers.contains(&worker) {
+            return Err(OrganizationError::NotInTag);
+        }
+
+        Ok(OrganizationEvent::WorkerRemovedFromTag {
+            tag_id,
             account: worker,
         })
     }
@@ -155,37 +432,131 @@ impl Organization {
         requesting_account: AccountId,
         editor: AccountId,
     ) -> Result<OrganizationEvent, OrganizationError> {
-        let tag = self
-            .tags
-            .iter()
-            .find(|&existing_tag| existing_tag.id == tag_id)
-            .ok_or(OrganizationError::TagDoesNotExist)?;
-        tag.authorized_editors
-            .iter()
-            .find(|&&editor| editor == requesting_account)
-            .ok_or(OrganizationError::NotAuthorized)?;
+        self.authorize_tag_edit(tag_id, requesting_account)?;
 
         Ok(OrganizationEvent::EditorAddedToTag {
-            tag_id: tag_id,
+            tag_id,
+            account: editor,
+        })
+    }
+
+    pub fn remove_editor_from_tag(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+        editor: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        let tag = self.authorize_tag_edit(tag_id, requesting_account)?;
+        if !tag.authorized_editors.contains(&editor) {
+            return Err(OrganizationError::NotInTag);
+        }
+
+        Ok(OrganizationEvent::EditorRemovedFromTag {
+            tag_id,
             account: editor,
         })
     }
 
+    fn authorize_tag_edit(
+        &self,
+        tag_id: TagId,
+        requesting_account: AccountId,
+    ) -> Result<&Tag, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::TagManage,
+            Scope::Tags(&HashSet::from([tag_id])),
+        )?;
+        self.tag(tag_id)
+    }
+
+    //every authorization check goes through here, role grants come from the organization's policy
+    pub fn authorize(
+        &self,
+        requesting_account: AccountId,
+        capability: Capability,
+        scope: Scope,
+    ) -> Result<(), OrganizationError> {
+        let link = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == requesting_account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        if self.policy.allows(link.account_type, capability) {
+            return Ok(());
+        }
+
+        match scope {
+            Scope::Tags(tags) if !tags.is_empty() && Capability::EDITOR.contains(&capability) => {
+                for tag_id in tags {
+                    if !self.editors_of_tag(*tag_id)?.contains(&requesting_account) {
+                        return Err(OrganizationError::NotAuthorized);
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(OrganizationError::NotAuthorized),
+        }
+    }
+
+    pub fn capabilities_of(&self, account: AccountId) -> HashSet<Capability> {
+        self.linked_accounts
+            .iter()
+            .find(|link| link.account == account)
+            .map(|link| self.policy.grants(link.account_type))
+            .unwrap_or_default()
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    //only the owner changes what the other roles may do
+    pub fn set_role_capabilities(
+        &self,
+        requesting_account: AccountId,
+        account_type: AccountType,
+        capabilities: HashSet<Capability>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        if self.owner() != Some(requesting_account) {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        if account_type == AccountType::Owner {
+            return Err(OrganizationError::InvalidRoleChange);
+        }
+
+        Ok(OrganizationEvent::RoleCapabilitiesSet {
+            account_type,
+            capabilities,
+        })
+    }
+
     pub fn link_account(
         &self,
         requesting_account: AccountId,
         worker: AccountId,
         link_type: AccountType,
     ) -> Result<OrganizationEvent, OrganizationError> {
-        self.linked_accounts
-            .iter()
-            .find(|link| {
-                link.account == requesting_account && link.account_type != AccountType::Worker
-            })
-            .ok_or(OrganizationError::NotAuthorized)?;
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        //ownership only changes hands through a transfer
         if link_type == AccountType::Owner {
             return Err(OrganizationError::NotAuthorized);
         }
+        //roles of linked accounts only change through change_role
+        if self
+            .linked_accounts
+            .iter()
+            .any(|link| link.account == worker)
+        {
+            return Err(OrganizationError::AccountAlreadyLinked);
+        }
 
         Ok(OrganizationEvent::AccountLinked {
             account: worker,
@@ -193,113 +564,1346 @@ impl Organization {
         })
     }
 
-    pub fn transfer_ownership(
+    //invitations can only hand out roles below the creator's own
+    pub fn create_invitation(
         &self,
         requesting_account: AccountId,
-        new_owner: AccountId,
+        account_type: AccountType,
+        tags: HashSet<TagId>,
+        max_uses: Option<u32>,
+        expires: Option<DateTime<Utc>>,
     ) -> Result<OrganizationEvent, OrganizationError> {
-        todo!();
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        let requester = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == requesting_account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        if account_type >= requester.account_type {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        if max_uses == Some(0) {
+            return Err(OrganizationError::InvitationUnavailable);
+        }
+        for &tag in &tags {
+            self.authorize_tag_edit(tag, requesting_account)?;
+        }
+
+        Ok(OrganizationEvent::InvitationCreated {
+            id: InvitationId::new(),
+            code: new_code(),
+            account_type,
+            tags,
+            max_uses,
+            expires,
+            created_by: requesting_account,
+        })
     }
 
-    pub fn assign_tasks_to_tags(
+    pub fn redeem_invitation(
         &self,
-        requesting_account: &AccountId,
-        tags: &HashSet<TagId>,
-        tasks: &Vec<CatalogueTaskId>,
-        assignment_type: &TaskAssignmentType,
-    ) -> Result<Vec<TaskInstance>, OrganizationError> {
-        //verify requesting account is an editor for all groups requested
-        let tags: Vec<&Tag> = self
-            .tags
+        code: &str,
+        account: AccountId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<OrganizationEvent>, OrganizationError> {
+        self.ensure_active()?;
+        let invitation = self
+            .invitations
             .iter()
-            .filter(|tag| tags.contains(&tag.id))
-            .collect();
-        let link = self
+            .find(|invitation| invitation.code == code)
+            .ok_or(OrganizationError::InvitationDoesNotExist)?;
+        if !invitation.is_usable(now) {
+            return Err(OrganizationError::InvitationUnavailable);
+        }
+        if self
             .linked_accounts
             .iter()
-            .find(|link| link.account == *requesting_account)
-            .ok_or(OrganizationError::NotInOrg)?;
+            .any(|link| link.account == account)
+        {
+            return Err(OrganizationError::AccountAlreadyLinked);
+        }
 
-        match link.account_type {
-            AccountType::Worker | AccountType::Admin => {
-                let is_authorized = tags
-                    .iter()
-                    .map(|tag| &tag.authorized_editors)
-                    .all(|editors| editors.contains(requesting_account));
+        let mut events = vec![
+            OrganizationEvent::InvitationRedeemed {
+                id: invitation.id,
+                account,
+            },
+            OrganizationEvent::AccountLinked {
+                account,
+                account_type: invitation.account_type,
+            },
+        ];
+        //tags removed since the invitation was created are skipped
+        events.extend(
+            self.tags
+                .iter()
+                .filter(|tag| invitation.tags.contains(&tag.id))
+                .map(|tag| OrganizationEvent::WorkerAddedToTag {
+                    tag_id: tag.id,
+                    account,
+                }),
+        );
+        Ok(events)
+    }
 
-                if is_authorized {
-                    Ok(())
-                } else {
-                    Err(OrganizationError::NotAuthorized)
-                }
-            }
-            AccountType::Owner => Ok(()),
-        }?;
+    pub fn revoke_invitation(
+        &self,
+        requesting_account: AccountId,
+        id: InvitationId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        let invitation = self
+            .invitations
+            .iter()
+            .find(|invitation| invitation.id == id)
+            .ok_or(OrganizationError::InvitationDoesNotExist)?;
+        if invitation.revoked {
+            return Err(OrganizationError::InvitationUnavailable);
+        }
 
-        //get workers that exist in all groups
-        let sets: Vec<&HashSet<AccountId>> = tags.iter().map(|tag| &tag.workers).collect();
+        Ok(OrganizationEvent::InvitationRevoked {
+            id,
+            revoked_by: requesting_account,
+        })
+    }
 
-        let workers: Vec<AccountId> = Vec::from_iter(match sets.len() {
-            0 => HashSet::new(),
-            _ => sets[1..].iter().fold(sets[0].clone(), |mut acc, set| {
-                acc.retain(|account| set.contains(account));
-                acc
-            }),
-        });
+    pub fn authorize_member(&self, requesting_account: AccountId) -> Result<(), OrganizationError> {
+        self.linked_accounts
+            .iter()
+            .find(|link| link.account == requesting_account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        Ok(())
+    }
 
-        if workers.len() == 0 {
-            return Err(OrganizationError::NoWorkers);
+    //accounts can only hand out and take away roles below their own, owner is only reached through a transfer
+    pub fn change_role(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+        account_type: AccountType,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        let requester = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == requesting_account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        let link = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == account)
+            .ok_or(OrganizationError::NotInOrg)?;
+
+        if account_type == AccountType::Owner || account_type == link.account_type {
+            return Err(OrganizationError::InvalidRoleChange);
+        }
+        if link.account_type >= requester.account_type || account_type >= requester.account_type {
+            return Err(OrganizationError::NotAuthorized);
         }
 
-        match assignment_type {
-            TaskAssignmentType::Random => {
-                let mut rng = rand::rng();
-                let out: Result<Vec<TaskInstance>, TaskDomainError> = tasks
-                    .iter()
-                    .map(|task| {
-                        let worker = workers.choose(&mut rng).unwrap();
-                        TaskInstance::new(
-                            TaskId::new(),
-                            *worker,
-                            *requesting_account,
-                            None,
-                            task.clone(),
-                            Pending,
-                        )
-                    })
-                    .collect();
+        Ok(OrganizationEvent::RoleChanged {
+            account,
+            from: link.account_type,
+            to: account_type,
+            changed_by: requesting_account,
+        })
+    }
 
-                Ok(out?)
-            }
-            TaskAssignmentType::LowestTasks => {
-                let mut out: Vec<TaskInstance> = Vec::new();
-                let mut workers_with_tasks: Vec<(&AccountId, usize)> = workers
+    pub fn set_handicap(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+        share: u32,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        if !self
+            .linked_accounts
+            .iter()
+            .any(|link| link.account == account)
+        {
+            return Err(OrganizationError::NotInOrg);
+        }
+        if share == 0 {
+            return Err(OrganizationError::InvalidShare);
+        }
+
+        Ok(OrganizationEvent::HandicapSet {
+            account,
+            share,
+            set_by: requesting_account,
+        })
+    }
+
+    //skills are normalized here so later comparisons with catalogue requirements are plain set lookups
+    pub fn set_profile(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+        birthdate: Option<NaiveDate>,
+        skills: impl IntoIterator<Item = String>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        if !self
+            .linked_accounts
+            .iter()
+            .any(|link| link.account == account)
+        {
+            return Err(OrganizationError::NotInOrg);
+        }
+
+        Ok(OrganizationEvent::ProfileSet {
+            account,
+            birthdate,
+            skills: normalize_skills(skills),
+            set_by: requesting_account,
+        })
+    }
+
+    //None when the account meets the requirements of the catalogue task, ages are taken on the local date
+    pub fn ineligibility(
+        &self,
+        account: AccountId,
+        task: &CatalogueTaskId,
+        now: DateTime<Utc>,
+    ) -> Option<Ineligibility> {
+        let requirements = self.catalogue_requirements.get(task)?;
+        let link = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == account)?;
+        let reasons = unmet(
+            requirements,
+            link.birthdate,
+            &link.skills,
+            self.settings.local_date(now),
+        );
+        (!reasons.is_empty()).then_some(Ineligibility {
+            account,
+            task: *task,
+            reasons,
+        })
+    }
+
+    //every worker left out of any of the tasks, and why, once per catalogue task
+    pub fn explain_eligibility(
+        &self,
+        workers: &[AccountId],
+        tasks: &[CatalogueTaskId],
+        now: DateTime<Utc>,
+    ) -> Vec<Ineligibility> {
+        let mut seen = HashSet::new();
+        tasks
+            .iter()
+            .filter(|task| seen.insert(**task))
+            .flat_map(|task| {
+                workers
+                    .iter()
+                    .filter_map(move |worker| self.ineligibility(*worker, task, now))
+            })
+            .collect()
+    }
+
+    fn ensure_eligible(
+        &self,
+        account: AccountId,
+        tasks: &[CatalogueTaskId],
+        now: DateTime<Utc>,
+    ) -> Result<(), OrganizationError> {
+        let ineligible = self.explain_eligibility(&[account], tasks, now);
+        match ineligible.is_empty() {
+            true => Ok(()),
+            false => Err(OrganizationError::Ineligible(ineligible)),
+        }
+    }
+
+    //the workers that meet the task's requirements, failing with why each one does not when none do
+    fn eligible_workers(
+        &self,
+        workers: &[AccountId],
+        task: &CatalogueTaskId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<AccountId>, OrganizationError> {
+        let eligible: Vec<AccountId> = workers
+            .iter()
+            .copied()
+            .filter(|worker| self.ineligibility(*worker, task, now).is_none())
+            .collect();
+        if eligible.is_empty() {
+            return Err(OrganizationError::Ineligible(self.explain_eligibility(
+                workers,
+                &[*task],
+                now,
+            )));
+        }
+        Ok(eligible)
+    }
+
+    pub fn caps(&self) -> &HashMap<CapTarget, WorkloadCaps> {
+        &self.caps
+    }
+
+    //caps of a tag apply to every worker of it, caps without any limit remove the ones set before
+    pub fn set_caps(
+        &self,
+        requesting_account: AccountId,
+        target: CapTarget,
+        caps: WorkloadCaps,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        match target {
+            CapTarget::Account(account) => {
+                self.ensure_active()?;
+                self.authorize(
+                    requesting_account,
+                    Capability::AccountManage,
+                    Scope::Organization,
+                )?;
+                if !self
+                    .linked_accounts
+                    .iter()
+                    .any(|link| link.account == account)
+                {
+                    return Err(OrganizationError::NotInOrg);
+                }
+            }
+            CapTarget::Tag(tag_id) => {
+                self.authorize_tag_edit(tag_id, requesting_account)?;
+            }
+        }
+
+        Ok(OrganizationEvent::WorkloadCapsSet {
+            target,
+            caps,
+            set_by: requesting_account,
+        })
+    }
+
+    //the account's own caps and those of every tag it works in, the tightest limit of each kind wins
+    pub fn caps_of(&self, account: AccountId) -> WorkloadCaps {
+        self.caps
+            .iter()
+            .filter(|(target, _)| match target {
+                CapTarget::Account(capped) => *capped == account,
+                CapTarget::Tag(tag) => self
+                    .workers_in_tags(&HashSet::from([*tag]))
+                    .contains(&account),
+            })
+            .fold(WorkloadCaps::default(), |acc, (_, caps)| acc.min(*caps))
+    }
+
+    fn workloads(&self, workers: &[AccountId]) -> HashMap<AccountId, Workload> {
+        self.linked_accounts
+            .iter()
+            .filter(|link| workers.contains(&link.account))
+            .map(|link| (link.account, link.workload.clone()))
+            .collect()
+    }
+
+    //a task counts towards the caps on the day it is due, or today when it has no deadline
+    fn due_day(&self, expires: Option<DateTime<Utc>>, now: DateTime<Utc>) -> DueDay {
+        let day = match expires {
+            Some(due) => self.settings.due_date(due),
+            None => self.settings.local_date(now),
+        };
+        DueDay {
+            day,
+            week: self.settings.first_day_of_week(day),
+        }
+    }
+
+    //eligible workers that can still take the task, spilling over past anyone whose caps it would break
+    fn candidates(
+        &self,
+        workers: &[AccountId],
+        task: &CatalogueTaskId,
+        now: DateTime<Utc>,
+        due: DueDay,
+        loads: &HashMap<AccountId, Workload>,
+    ) -> Result<Vec<AccountId>, OrganizationError> {
+        let effort = self.effort_of(task);
+        let nothing = Workload::default();
+        let within: Vec<AccountId> = self
+            .eligible_workers(workers, task, now)?
+            .into_iter()
+            .filter(|worker| {
+                loads
+                    .get(worker)
+                    .unwrap_or(&nothing)
+                    .fits(&self.caps_of(*worker), effort, due)
+            })
+            .collect();
+        if within.is_empty() {
+            return Err(OrganizationError::CapReached);
+        }
+        Ok(within)
+    }
+
+    //counts the task towards the account's caps within one assignment
+    fn add_load(
+        &self,
+        loads: &mut HashMap<AccountId, Workload>,
+        account: AccountId,
+        task: &CatalogueTaskId,
+        due: DueDay,
+    ) -> Result<(), OrganizationError> {
+        let load = loads.entry(account).or_default();
+        let effort = self.effort_of(task);
+        if !load.fits(&self.caps_of(account), effort, due) {
+            return Err(OrganizationError::CapReached);
+        }
+        load.add(due, effort);
+        Ok(())
+    }
+
+    fn ensure_within_caps(
+        &self,
+        account: AccountId,
+        tasks: &[CatalogueTaskId],
+        due: DueDay,
+    ) -> Result<(), OrganizationError> {
+        let mut loads = self.workloads(&[account]);
+        for task in tasks {
+            self.add_load(&mut loads, account, task, due)?;
+        }
+        Ok(())
+    }
+
+    pub fn unavailability(&self) -> &[AccountUnavailability] {
+        &self.unavailability
+    }
+
+    //an account is unavailable when its periods leave it no time between now and when the task is due
+    pub fn is_available(
+        &self,
+        account: AccountId,
+        now: DateTime<Utc>,
+        due: Option<DateTime<Utc>>,
+    ) -> bool {
+        let periods = self
+            .unavailability
+            .iter()
+            .filter(|period| period.account == account)
+            .map(|period| &period.unavailability);
+        !covers(periods, &self.settings, now, due)
+    }
+
+    //accounts record their own periods, anyone else needs to manage accounts
+    pub fn add_unavailability(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+        unavailability: Unavailability,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize_unavailability(requesting_account, account)?;
+        unavailability.validate()?;
+
+        Ok(OrganizationEvent::UnavailabilityAdded {
+            id: UnavailabilityId::new(),
+            account,
+            unavailability,
+            added_by: requesting_account,
+        })
+    }
+
+    pub fn remove_unavailability(
+        &self,
+        requesting_account: AccountId,
+        id: UnavailabilityId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        let period = self
+            .unavailability
+            .iter()
+            .find(|period| period.id == id)
+            .ok_or(OrganizationError::UnavailabilityDoesNotExist)?;
+        self.authorize_unavailability(requesting_account, period.account)?;
+
+        Ok(OrganizationEvent::UnavailabilityRemoved {
+            id,
+            removed_by: requesting_account,
+        })
+    }
+
+    fn authorize_unavailability(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+    ) -> Result<(), OrganizationError> {
+        if !self
+            .linked_accounts
+            .iter()
+            .any(|link| link.account == account)
+        {
+            return Err(OrganizationError::NotInOrg);
+        }
+        if account == requesting_account {
+            return self.authorize_member(requesting_account);
+        }
+
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )
+    }
+
+    //owners can unlink anyone else, admins can only unlink workers
+    pub fn unlink_account(
+        &self,
+        requesting_account: AccountId,
+        account: AccountId,
+    ) -> Result<Vec<OrganizationEvent>, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::AccountManage,
+            Scope::Organization,
+        )?;
+        let requester = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == requesting_account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        let link = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == account)
+            .ok_or(OrganizationError::NotInOrg)?;
+
+        match (requester.account_type, link.account_type) {
+            (_, AccountType::Owner) => Err(OrganizationError::NotAuthorized),
+            (AccountType::Owner, _) | (AccountType::Admin, AccountType::Worker) => {
+                Ok(self.unlink_events(account))
+            }
+            _ => Err(OrganizationError::NotAuthorized),
+        }
+    }
+
+    //the owner has to hand the organization over before leaving
+    pub fn leave(&self, account: AccountId) -> Result<Vec<OrganizationEvent>, OrganizationError> {
+        self.ensure_active()?;
+        let link = self
+            .linked_accounts
+            .iter()
+            .find(|link| link.account == account)
+            .ok_or(OrganizationError::NotInOrg)?;
+        if link.account_type == AccountType::Owner {
+            return Err(OrganizationError::OwnerCannotLeave);
+        }
+
+        Ok(self.unlink_events(account))
+    }
+
+    //repeating tasks assigned to or set up by the account could never be assigned again
+    fn unlink_events(&self, account: AccountId) -> Vec<OrganizationEvent> {
+        let mut events = Vec::new();
+        for tag in &self.tags {
+            if tag.workers.contains(&account) {
+                events.push(OrganizationEvent::WorkerRemovedFromTag {
+                    tag_id: tag.id,
+                    account,
+                });
+            }
+            if tag.authorized_editors.contains(&account) {
+                events.push(OrganizationEvent::EditorRemovedFromTag {
+                    tag_id: tag.id,
+                    account,
+                });
+            }
+        }
+        events.extend(
+            self.repeating_tasks
+                .iter()
+                .filter(|repeat| {
+                    repeat.requesting_account == account
+                        || repeat.assigned_to == AssignmentType::Account(account)
+                })
+                .map(|repeat| OrganizationEvent::RepeatingTaskRemoved { id: repeat.id }),
+        );
+        events.push(OrganizationEvent::AccountUnlinked { account });
+        events
+    }
+
+    //called on the organization after the account was unlinked, tasks that cannot be reassigned stay orphaned
+    //task events for the released tasks, and organization events for the rotations they moved
+    pub fn release_tasks(
+        &self,
+        requesting_account: AccountId,
+        tasks: &[TaskInstance],
+        handling: &PendingTaskHandling,
+        now: DateTime<Utc>,
+        rng: &mut impl RngCore,
+    ) -> Result<(Vec<TaskEvent>, Vec<OrganizationEvent>), OrganizationError> {
+        let mut events = Vec::new();
+        let mut rotations = self.rotations.clone();
+        for task in tasks {
+            match handling {
+                PendingTaskHandling::Cancel => events.push(task.cancel(requesting_account)?),
+                PendingTaskHandling::Reassign(assignment_type) => {
+                    if task.tags().is_empty() {
+                        continue;
+                    }
+                    //whoever assigned the task may have left as well
+                    let assigned_by = match self
+                        .linked_accounts
+                        .iter()
+                        .any(|link| link.account == task.assigned_by())
+                    {
+                        true => task.assigned_by(),
+                        false => self.owner().unwrap_or(task.assigned_by()),
+                    };
+                    let workers = self.workers_in_tags(task.tags());
+                    let reassigned = match self.assign_to_workers(
+                        &assigned_by,
+                        &workers,
+                        task.tags(),
+                        &[task.catalogue_id()],
+                        assignment_type,
+                        now,
+                        task.expires(),
+                        &mut rotations,
+                        rng,
+                    ) {
+                        Ok(reassigned) => reassigned,
+                        Err(
+                            OrganizationError::NoWorkers
+                            | OrganizationError::Unavailable
+                            | OrganizationError::Ineligible(_)
+                            | OrganizationError::CapReached,
+                        ) => continue,
+                        Err(error) => return Err(error),
+                    };
+                    events.push(task.cancel(requesting_account)?);
+                    events.extend(
+                        reassigned
+                            .into_iter()
+                            .map(|reassigned| reassigned.as_reassignment_of(task).create()),
+                    );
+                }
+                PendingTaskHandling::Orphan => {}
+            }
+        }
+        Ok((events, self.rotation_events(rotations)))
+    }
+
+    pub fn release_orphaned_tasks(
+        &self,
+        requesting_account: AccountId,
+        tasks: &[TaskInstance],
+        handling: &PendingTaskHandling,
+        now: DateTime<Utc>,
+        rng: &mut impl RngCore,
+    ) -> Result<(Vec<TaskEvent>, Vec<OrganizationEvent>), OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::TaskAssign,
+            Scope::Organization,
+        )?;
+        if tasks
+            .iter()
+            .any(|task| !self.orphaned_tasks.contains(&task.id()))
+        {
+            return Err(OrganizationError::NotAuthorized);
+        }
+
+        self.release_tasks(requesting_account, tasks, handling, now, rng)
+    }
+
+    //offers the organization to another linked account, a newer offer replaces an open one
+    pub fn transfer_ownership(
+        &self,
+        requesting_account: AccountId,
+        new_owner: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        if self.owner() != Some(requesting_account) {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        if new_owner == requesting_account {
+            return Err(OrganizationError::InvalidOwnershipTransfer);
+        }
+        self.linked_accounts
+            .iter()
+            .find(|link| link.account == new_owner)
+            .ok_or(OrganizationError::NotInOrg)?;
+
+        Ok(OrganizationEvent::OwnershipOffered {
+            from: requesting_account,
+            to: new_owner,
+        })
+    }
+
+    pub fn accept_ownership(
+        &self,
+        requesting_account: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        if self.ownership_offer != Some(requesting_account) {
+            return Err(OrganizationError::NoOwnershipOffer);
+        }
+        let owner = self
+            .owner()
+            .ok_or(OrganizationError::InvalidOwnershipTransfer)?;
+
+        Ok(OrganizationEvent::OwnershipTransferred {
+            from: owner,
+            to: requesting_account,
+        })
+    }
+
+    pub fn decline_ownership(
+        &self,
+        requesting_account: AccountId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        if self.ownership_offer != Some(requesting_account) {
+            return Err(OrganizationError::NoOwnershipOffer);
+        }
+
+        Ok(OrganizationEvent::OwnershipDeclined {
+            account: requesting_account,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn assign_tasks_to_tags(
+        &self,
+        requesting_account: &AccountId,
+        tags: &HashSet<TagId>,
+        tasks: &[CatalogueTaskId],
+        assignment_type: &TaskAssignmentType,
+        now: DateTime<Utc>,
+        expires: Option<DateTime<Utc>>,
+        rng: &mut impl RngCore,
+    ) -> Result<Assignment, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            *requesting_account,
+            Capability::TaskAssign,
+            Scope::Tags(tags),
+        )?;
+
+        let workers = self.workers_in_tags(tags);
+        let mut rotations = self.rotations.clone();
+        let assigned = self
+            .assign_to_workers(
+                requesting_account,
+                &workers,
+                tags,
+                tasks,
+                assignment_type,
+                now,
+                expires,
+                &mut rotations,
+                rng,
+            )?
+            .into_iter()
+            .map(|task| task.with_tags(tags.clone()))
+            .collect();
+        Ok(Assignment {
+            tasks: assigned,
+            events: self.rotation_events(rotations),
+            ineligible: self.explain_eligibility(&workers, tasks, now),
+        })
+    }
+
+    //open tasks for any worker of the tags to claim
+    pub fn post_tasks(
+        &self,
+        requesting_account: AccountId,
+        tags: &HashSet<TagId>,
+        tasks: &[CatalogueTaskId],
+        expires: Option<DateTime<Utc>>,
+        claim_deadline: Option<ClaimDeadline>,
+    ) -> Result<Vec<TaskInstance>, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::TaskAssign,
+            Scope::Tags(tags),
+        )?;
+        if self.workers_in_tags(tags).is_empty() {
+            return Err(OrganizationError::NoWorkers);
+        }
+
+        let out: Result<Vec<TaskInstance>, TaskDomainError> = tasks
+            .iter()
+            .map(|task| {
+                Ok(TaskInstance::new(
+                    TaskId::new(),
+                    self.id,
+                    requesting_account,
+                    requesting_account,
+                    expires,
+                    *task,
+                    TaskStatus::Open,
+                )?
+                .with_tags(tags.clone())
+                .with_pool(true, claim_deadline.clone()))
+            })
+            .collect();
+        Ok(out?)
+    }
+
+    //any worker of the task's tags may claim it
+    pub fn claim_task(
+        &self,
+        requesting_account: AccountId,
+        task: &TaskInstance,
+        now: DateTime<Utc>,
+    ) -> Result<TaskEvent, OrganizationError> {
+        self.ensure_active()?;
+        if task.organization() != self.id {
+            return Err(OrganizationError::NotInOrg);
+        }
+        if !self
+            .workers_in_tags(task.tags())
+            .contains(&requesting_account)
+        {
+            return Err(OrganizationError::NotInTag);
+        }
+        if !self.is_available(requesting_account, now, task.expires()) {
+            return Err(OrganizationError::Unavailable);
+        }
+        self.ensure_eligible(requesting_account, &[task.catalogue_id()], now)?;
+        self.ensure_within_caps(
+            requesting_account,
+            &[task.catalogue_id()],
+            self.due_day(task.expires(), now),
+        )?;
+
+        Ok(task.claim(requesting_account)?)
+    }
+
+    pub fn unclaim_task(
+        &self,
+        requesting_account: AccountId,
+        task: &TaskInstance,
+    ) -> Result<TaskEvent, OrganizationError> {
+        self.ensure_active()?;
+        if task.organization() != self.id {
+            return Err(OrganizationError::NotInOrg);
+        }
+
+        Ok(task.unclaim(requesting_account)?)
+    }
+
+    //assigns a pooled task nobody claimed in time the way its claim deadline asks for
+    pub fn fall_back(
+        &self,
+        task: &TaskInstance,
+        now: DateTime<Utc>,
+        rng: &mut impl RngCore,
+    ) -> Result<(Vec<TaskEvent>, Vec<OrganizationEvent>), OrganizationError> {
+        self.ensure_active()?;
+        let deadline = task
+            .claim_deadline()
+            .ok_or(TaskDomainError::StatusNotApplicable)?;
+        let passed = task.pass_claim_deadline()?;
+
+        let workers = self.workers_in_tags(task.tags());
+        let mut rotations = self.rotations.clone();
+        let assigned = self.assign_to_workers(
+            &task.assigned_by(),
+            &workers,
+            task.tags(),
+            &[task.catalogue_id()],
+            &deadline.fallback,
+            now,
+            task.expires(),
+            &mut rotations,
+            rng,
+        )?;
+
+        let mut events = vec![passed];
+        events.extend(assigned.into_iter().map(|assigned| {
+            assigned
+                .with_tags(task.tags().clone())
+                .with_reassignment(Some(task.id()), task.reassignments())
+                .create()
+        }));
+        Ok((events, self.rotation_events(rotations)))
+    }
+
+    pub fn trades(&self) -> &[Trade] {
+        &self.trades
+    }
+
+    fn trade(&self, id: TradeId) -> Result<&Trade, OrganizationError> {
+        self.trades
+            .iter()
+            .find(|trade| trade.id == id)
+            .ok_or(OrganizationError::TradeDoesNotExist)
+    }
+
+    //hands the offered task to the counterparty, or swaps it for one of theirs when a task is requested back
+    pub fn propose_trade(
+        &self,
+        requesting_account: AccountId,
+        counterparty: AccountId,
+        offered: &TaskInstance,
+        requested: Option<&TaskInstance>,
+        now: DateTime<Utc>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize_member(requesting_account)?;
+        if counterparty == requesting_account
+            || !self
+                .linked_accounts
+                .iter()
+                .any(|link| link.account == counterparty)
+        {
+            return Err(OrganizationError::InvalidTrade);
+        }
+
+        let mut approvers = BTreeSet::new();
+        let sides = [(offered, requesting_account, counterparty)]
+            .into_iter()
+            .chain(requested.map(|requested| (requested, counterparty, requesting_account)));
+        for (task, from, to) in sides {
+            if task.organization() != self.id
+                || task.assigned_to() != from
+                || *task.status() != Pending
+            {
+                return Err(OrganizationError::InvalidTrade);
+            }
+            //tasks handed out through tags can only go to someone else in the tags
+            if !task.tags().is_empty() && !self.workers_in_tags(task.tags()).contains(&to) {
+                return Err(OrganizationError::NotInTag);
+            }
+            if self.trades.iter().any(|trade| trade.involves(task.id())) {
+                return Err(OrganizationError::TaskAlreadyTraded);
+            }
+            self.ensure_eligible(to, &[task.catalogue_id()], now)?;
+            approvers.insert(task.assigned_by());
+        }
+
+        Ok(OrganizationEvent::TradeProposed {
+            id: TradeId::new(),
+            proposed_by: requesting_account,
+            counterparty,
+            offered: offered.id(),
+            requested: requested.map(|task| task.id()),
+            approvers,
+        })
+    }
+
+    pub fn accept_trade(
+        &self,
+        requesting_account: AccountId,
+        id: TradeId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        let trade = self.trade(id)?;
+        if trade.counterparty != requesting_account {
+            return Err(OrganizationError::NotAuthorized);
+        }
+        if trade.accepted {
+            return Err(OrganizationError::InvalidTrade);
+        }
+
+        Ok(OrganizationEvent::TradeAccepted { id })
+    }
+
+    pub fn approve_trade(
+        &self,
+        requesting_account: AccountId,
+        id: TradeId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        let trade = self.trade(id)?;
+        if !trade.approvers.contains(&requesting_account) {
+            return Err(OrganizationError::NotAuthorized);
+        }
+
+        Ok(OrganizationEvent::TradeApproved {
+            id,
+            approved_by: requesting_account,
+        })
+    }
+
+    //any party or approver can call a trade off
+    pub fn decline_trade(
+        &self,
+        requesting_account: AccountId,
+        id: TradeId,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        let trade = self.trade(id)?;
+        if trade.proposed_by != requesting_account
+            && trade.counterparty != requesting_account
+            && !trade.approvers.contains(&requesting_account)
+        {
+            return Err(OrganizationError::NotAuthorized);
+        }
+
+        Ok(OrganizationEvent::TradeDeclined {
+            id,
+            declined_by: requesting_account,
+        })
+    }
+
+    //accepted, and approved when the organization asks for it
+    pub fn trade_ready(&self, id: TradeId) -> bool {
+        self.trade(id).is_ok_and(|trade| {
+            trade.accepted && (!self.settings.trades_need_approval || trade.approved())
+        })
+    }
+
+    //the tasks are checked again since they may have been finished or handed on after the proposal
+    pub fn complete_trade(
+        &self,
+        id: TradeId,
+        offered: &TaskInstance,
+        requested: Option<&TaskInstance>,
+    ) -> Result<(OrganizationEvent, Vec<TaskEvent>), OrganizationError> {
+        self.ensure_active()?;
+        let trade = self.trade(id)?;
+        if !self.trade_ready(id)
+            || offered.id() != trade.offered
+            || requested.map(|task| task.id()) != trade.requested
+        {
+            return Err(OrganizationError::InvalidTrade);
+        }
+
+        let mut events = vec![offered
+            .hand_over(trade.proposed_by, trade.counterparty, id)
+            .map_err(|_| OrganizationError::InvalidTrade)?];
+        if let Some(requested) = requested {
+            events.push(
+                requested
+                    .hand_over(trade.counterparty, trade.proposed_by, id)
+                    .map_err(|_| OrganizationError::InvalidTrade)?,
+            );
+        }
+        Ok((OrganizationEvent::TradeCompleted { id }, events))
+    }
+
+    //takes back a previewed assignment, possibly with other assignees, checking it as if it were new
+    pub fn confirm_assignment(
+        &self,
+        requesting_account: &AccountId,
+        tags: &HashSet<TagId>,
+        proposed: &[TaskInstance],
+        assignment_type: &TaskAssignmentType,
+        now: DateTime<Utc>,
+    ) -> Result<Assignment, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            *requesting_account,
+            Capability::TaskAssign,
+            Scope::Tags(tags),
+        )?;
+
+        let workers = self.workers_in_tags(tags);
+        let mut rotations = self.rotations.clone();
+        let mut loads = self.workloads(&workers);
+        let mut tasks = Vec::new();
+        for task in proposed {
+            if !workers.contains(&task.assigned_to()) {
+                return Err(OrganizationError::NotInTag);
+            }
+            if !self.is_available(task.assigned_to(), now, task.expires()) {
+                return Err(OrganizationError::Unavailable);
+            }
+            self.ensure_eligible(task.assigned_to(), &[task.catalogue_id()], now)?;
+            self.add_load(
+                &mut loads,
+                task.assigned_to(),
+                &task.catalogue_id(),
+                self.due_day(task.expires(), now),
+            )?;
+            //a round robin continues from whoever ended up with the task
+            if let TaskAssignmentType::RoundRobin { rotation } = assignment_type {
+                let key = match rotation {
+                    Rotation::Tags => RotationKey::Tags(tags.iter().copied().collect()),
+                    Rotation::Task => RotationKey::Task(task.catalogue_id()),
+                };
+                rotations.set(key, task.assigned_to());
+            }
+            let mut confirmed = TaskInstance::new(
+                TaskId::new(),
+                self.id,
+                task.assigned_to(),
+                *requesting_account,
+                task.expires(),
+                task.catalogue_id(),
+                Pending,
+            )?
+            .with_tags(tags.clone());
+            //a random pick is only kept when it replays from workers of the tags to the same worker
+            if let Some(seed) = task.seed() {
+                if !task
+                    .candidates()
                     .iter()
-                    .map(|worker| {
-                        (
-                            worker,
-                            self.linked_accounts
-                                .iter()
-                                .find(|link| link.account == *worker)
-                                .map(|link| link.tasks.len())
-                                .unwrap(),
-                        )
+                    .all(|candidate| workers.contains(candidate))
+                    || random_pick(task.candidates(), seed) != Some(task.assigned_to())
+                {
+                    return Err(OrganizationError::RandomPickMismatch);
+                }
+                confirmed = confirmed.with_random_pick(seed, task.candidates().to_vec());
+            }
+            tasks.push(confirmed);
+        }
+
+        Ok(Assignment {
+            tasks,
+            events: self.rotation_events(rotations),
+            ineligible: Vec::new(),
+        })
+    }
+
+    //spreads the tasks over the days of the week the date falls into, a task listed twice is done twice
+    //each occurrence is due at the end of its day, days that are already over are left out
+    pub fn plan_week(
+        &self,
+        requesting_account: &AccountId,
+        tags: &HashSet<TagId>,
+        tasks: &[CatalogueTaskId],
+        week: NaiveDate,
+        now: DateTime<Utc>,
+        caps: &HashMap<AccountId, WorkloadCaps>,
+    ) -> Result<Assignment, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            *requesting_account,
+            Capability::TaskAssign,
+            Scope::Tags(tags),
+        )?;
+
+        let mut workers = self.workers_in_tags(tags);
+        if workers.is_empty() {
+            return Err(OrganizationError::NoWorkers);
+        }
+        //sorted so the same week plans the same way
+        workers.sort();
+
+        let start = self
+            .settings
+            .week_start_of(self.settings.start_of_day(week));
+        let days: Vec<(DateTime<Utc>, DateTime<Utc>)> = (0..7)
+            .filter_map(|day| start.checked_add_days(Days::new(day)))
+            .map(|date| {
+                let begins = self.settings.start_of_day(date);
+                (begins, self.settings.end_of_day(begins))
+            })
+            .filter(|(_, due)| *due > now)
+            .collect();
+        if days.is_empty() {
+            return Err(OrganizationError::CannotPlan);
+        }
+        //tasks the workers already have due in the week count towards their caps
+        let plan_workers: Vec<PlanWorker> = workers
+            .iter()
+            .map(|worker| {
+                let link = self
+                    .linked_accounts
+                    .iter()
+                    .find(|link| link.account == *worker);
+                let workload = link.map(|link| &link.workload);
+                PlanWorker {
+                    account: *worker,
+                    share: link.map(|link| link.share).unwrap_or(FULL_SHARE),
+                    caps: self
+                        .caps_of(*worker)
+                        .min(caps.get(worker).copied().unwrap_or_default()),
+                    due: days
+                        .iter()
+                        .map(|(begins, _)| {
+                            workload.map_or(0, |workload| {
+                                workload.on(self.settings.local_date(*begins)).tasks
+                            })
+                        })
+                        .collect(),
+                    week: workload
+                        .map(|workload| workload.week(start))
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        let available: Vec<Slot> = (0..workers.len())
+            .flat_map(|worker| (0..days.len()).map(move |day| Slot { worker, day }))
+            .filter(|slot| {
+                let (begins, due) = days[slot.day];
+                self.is_available(workers[slot.worker], begins.max(now), Some(due))
+            })
+            .collect();
+        let mut items = Vec::new();
+        for task in tasks {
+            let eligible = self.eligible_workers(&workers, task, now)?;
+            items.push(PlanItem {
+                effort: self.effort_of(task),
+                options: available
+                    .iter()
+                    .copied()
+                    .filter(|slot| eligible.contains(&workers[slot.worker]))
+                    .collect(),
+            });
+        }
+
+        let slots = plan(&plan_workers, days.len(), &items).ok_or(OrganizationError::CannotPlan)?;
+        let out: Result<Vec<TaskInstance>, TaskDomainError> = tasks
+            .iter()
+            .zip(slots)
+            .map(|(task, slot)| {
+                Ok(TaskInstance::new(
+                    TaskId::new(),
+                    self.id,
+                    workers[slot.worker],
+                    *requesting_account,
+                    Some(days[slot.day].1),
+                    *task,
+                    Pending,
+                )?
+                .with_tags(tags.clone()))
+            })
+            .collect();
+
+        Ok(Assignment {
+            tasks: out?,
+            events: Vec::new(),
+            ineligible: self.explain_eligibility(&workers, tasks, now),
+        })
+    }
+
+    fn rotation_events(&self, rotations: Rotations) -> Vec<OrganizationEvent> {
+        rotations
+            .changes_since(&self.rotations)
+            .into_iter()
+            .map(|(key, last)| OrganizationEvent::RotationAdvanced { key, last })
+            .collect()
+    }
+
+    //get workers that exist in all groups, counting the ones inherited from parent tags
+    fn workers_in_tags(&self, tags: &HashSet<TagId>) -> Vec<AccountId> {
+        let sets: Vec<HashSet<AccountId>> = self
+            .tags
+            .iter()
+            .filter(|tag| tags.contains(&tag.id))
+            .map(|tag| {
+                self.lineage(tag)
+                    .flat_map(|tag| tag.workers.iter().copied())
+                    .collect()
+            })
+            .collect();
+
+        Vec::from_iter(match sets.len() {
+            0 => HashSet::new(),
+            _ => sets[1..].iter().fold(sets[0].clone(), |mut acc, set| {
+                acc.retain(|account| set.contains(account));
+                acc
+            }),
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_to_workers(
+        &self,
+        requesting_account: &AccountId,
+        workers: &[AccountId],
+        tags: &HashSet<TagId>,
+        tasks: &[CatalogueTaskId],
+        assignment_type: &TaskAssignmentType,
+        now: DateTime<Utc>,
+        expires: Option<DateTime<Utc>>,
+        rotations: &mut Rotations,
+        rng: &mut impl RngCore,
+    ) -> Result<Vec<TaskInstance>, OrganizationError> {
+        if workers.is_empty() {
+            return Err(OrganizationError::NoWorkers);
+        }
+        let available: Vec<AccountId> = workers
+            .iter()
+            .copied()
+            .filter(|worker| self.is_available(*worker, now, expires))
+            .collect();
+        if available.is_empty() {
+            return Err(OrganizationError::Unavailable);
+        }
+        let in_tags = workers;
+        let workers = available.as_slice();
+        let mut loads = self.workloads(workers);
+        let due = self.due_day(expires, now);
+
+        match assignment_type {
+            //every pick gets its own seed so it can be replayed on its own with random_pick
+            TaskAssignmentType::Random => tasks
+                .iter()
+                .map(|task| {
+                    let eligible = self.candidates(workers, task, now, due, &loads)?;
+                    let seed = rng.next_u64();
+                    let worker =
+                        random_pick(&eligible, seed).ok_or(OrganizationError::NoWorkers)?;
+                    self.add_load(&mut loads, worker, task, due)?;
+                    Ok(TaskInstance::new(
+                        TaskId::new(),
+                        self.id,
+                        worker,
+                        *requesting_account,
+                        expires,
+                        *task,
+                        Pending,
+                    )?
+                    .with_random_pick(seed, eligible))
+                })
+                .collect(),
+            TaskAssignmentType::LowestTasks => {
+                let mut out: Vec<TaskInstance> = Vec::new();
+                let mut workers_with_tasks: Vec<(&AccountId, usize)> = workers
+                    .iter()
+                    .filter_map(|worker| {
+                        self.linked_accounts
+                            .iter()
+                            .find(|link| link.account == *worker)
+                            .map(|link| (worker, link.tasks.len()))
                     })
                     .collect();
 
                 for task in tasks {
-                    let min = workers_with_tasks.iter_mut().min_by_key(|worker| worker.1);
+                    let eligible = self.candidates(workers, task, now, due, &loads)?;
+                    let min = workers_with_tasks
+                        .iter_mut()
+                        .filter(|worker| eligible.contains(worker.0))
+                        .min_by_key(|worker| worker.1);
                     match min {
                         Some(worker) => {
                             let task_newd = TaskInstance::new(
                                 TaskId::new(),
-                                worker.0.clone(),
+                                self.id,
+                                *worker.0,
                                 *requesting_account,
-                                None,
+                                expires,
                                 *task,
                                 Pending,
                             )?;
                             out.push(task_newd);
+                            self.add_load(&mut loads, *worker.0, task, due)?;
                             worker.1 += 1;
                         }
                         None => return Err(OrganizationError::NoWorkers),
@@ -312,31 +1916,33 @@ impl Organization {
                 let mut out: Vec<TaskInstance> = Vec::new();
                 let mut workers_with_tasks: Vec<(&AccountId, usize)> = workers
                     .iter()
-                    .map(|worker| {
-                        (
-                            worker,
-                            self.linked_accounts
-                                .iter()
-                                .find(|link| link.account == *worker)
-                                .map(|link| link.tasks.len())
-                                .unwrap(),
-                        )
+                    .filter_map(|worker| {
+                        self.linked_accounts
+                            .iter()
+                            .find(|link| link.account == *worker)
+                            .map(|link| (worker, link.tasks.len()))
                     })
                     .collect();
 
                 for task in tasks {
-                    let max = workers_with_tasks.iter_mut().max_by_key(|worker| worker.1);
+                    let eligible = self.candidates(workers, task, now, due, &loads)?;
+                    let max = workers_with_tasks
+                        .iter_mut()
+                        .filter(|worker| eligible.contains(worker.0))
+                        .max_by_key(|worker| worker.1);
                     match max {
                         Some(worker) => {
                             let task_newd = TaskInstance::new(
                                 TaskId::new(),
-                                worker.0.clone(),
+                                self.id,
+                                *worker.0,
                                 *requesting_account,
-                                None,
+                                expires,
                                 *task,
                                 Pending,
                             )?;
                             out.push(task_newd);
+                            self.add_load(&mut loads, *worker.0, task, due)?;
                             worker.1 += 1;
                         }
                         None => return Err(OrganizationError::NoWorkers),
@@ -345,39 +1951,108 @@ impl Organization {
 
                 Ok(out)
             }
-            TaskAssignmentType::Copy => {
-                let output: Result<Vec<TaskInstance>, TaskDomainError> = tasks
+            TaskAssignmentType::LowestEffort => {
+                let mut out: Vec<TaskInstance> = Vec::new();
+                let mut links: Vec<(AccountLink, usize)> = workers
                     .iter()
-                    .flat_map(|&task| {
-                        let mut out = Vec::new();
-                        for worker in &workers {
+                    .filter_map(|worker| {
+                        self.linked_accounts
+                            .iter()
+                            .find(|link| link.account == *worker)
+                            .map(|link| (link.clone(), link.tasks.len()))
+                    })
+                    .collect();
+
+                for task in tasks {
+                    let eligible = self.candidates(workers, task, now, due, &loads)?;
+                    //ties go to fewer pending tasks, then to account order so results are stable
+                    let min = links
+                        .iter_mut()
+                        .filter(|(link, _)| eligible.contains(&link.account))
+                        .min_by_key(|(link, pending)| {
+                            (link.weighted_effort(), *pending, link.account)
+                        });
+                    match min {
+                        Some((link, pending)) => {
                             out.push(TaskInstance::new(
                                 TaskId::new(),
-                                *worker,
+                                self.id,
+                                link.account,
                                 *requesting_account,
-                                None,
-                                task.clone(),
+                                expires,
+                                *task,
                                 Pending,
-                            ));
+                            )?);
+                            self.add_load(&mut loads, link.account, task, due)?;
+                            link.effort = link.effort.saturating_add(self.effort_of(task));
+                            *pending += 1;
                         }
-                        out
-                    })
-                    .collect();
+                        None => return Err(OrganizationError::NoWorkers),
+                    };
+                }
+
+                Ok(out)
+            }
+            TaskAssignmentType::RoundRobin { rotation } => tasks
+                .iter()
+                .map(|task| {
+                    let key = match rotation {
+                        Rotation::Tags => RotationKey::Tags(tags.iter().copied().collect()),
+                        Rotation::Task => RotationKey::Task(*task),
+                    };
+                    let eligible = self.candidates(workers, task, now, due, &loads)?;
+                    let worker = rotations
+                        .next(key, &eligible)
+                        .ok_or(OrganizationError::NoWorkers)?;
+                    self.add_load(&mut loads, worker, task, due)?;
+                    Ok(TaskInstance::new(
+                        TaskId::new(),
+                        self.id,
+                        worker,
+                        *requesting_account,
+                        expires,
+                        *task,
+                        Pending,
+                    )?)
+                })
+                .collect(),
+            //everyone eligible gets a copy, unless their caps are reached
+            TaskAssignmentType::Copy => {
+                let mut out = Vec::new();
+                for task in tasks {
+                    for worker in self.candidates(workers, task, now, due, &loads)? {
+                        self.add_load(&mut loads, worker, task, due)?;
+                        out.push(TaskInstance::new(
+                            TaskId::new(),
+                            self.id,
+                            worker,
+                            *requesting_account,
+                            expires,
+                            *task,
+                            Pending,
+                        )?);
+                    }
+                }
 
-                Ok(output?)
+                Ok(out)
             }
             TaskAssignmentType::ToAccount { account } => {
                 match workers.iter().find(|&worker| *worker == *account) {
                     Some(_) => {
+                        self.ensure_eligible(*account, tasks, now)?;
+                        for task in tasks {
+                            self.add_load(&mut loads, *account, task, due)?;
+                        }
                         let output: Result<Vec<TaskInstance>, TaskDomainError> = tasks
                             .iter()
                             .map(|task| {
                                 TaskInstance::new(
                                     TaskId::new(),
+                                    self.id,
                                     *account,
                                     *requesting_account,
-                                    None,
-                                    task.clone(),
+                                    expires,
+                                    *task,
                                     Pending,
                                 )
                             })
@@ -385,52 +2060,650 @@ impl Organization {
 
                         Ok(output?)
                     }
-                    None => Err(OrganizationError::NoWorkers),
+                    None if in_tags.contains(account) => Err(OrganizationError::Unavailable),
+                    None => Err(OrganizationError::NoWorkers),
+                }
+            }
+        }
+    }
+
+    pub fn assign_tasks_to_account(
+        &self,
+        requesting_account: AccountId,
+        worker: AccountId,
+        tasks: &[CatalogueTaskId],
+        now: DateTime<Utc>,
+        expires: Option<DateTime<Utc>>,
+    ) -> Result<Vec<TaskInstance>, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize_account_assignment(requesting_account, worker)?;
+        if !self.is_available(worker, now, expires) {
+            return Err(OrganizationError::Unavailable);
+        }
+        self.ensure_eligible(worker, tasks, now)?;
+        self.ensure_within_caps(worker, tasks, self.due_day(expires, now))?;
+
+        let out: Result<Vec<TaskInstance>, TaskDomainError> = tasks
+            .iter()
+            .map(|task| {
+                TaskInstance::new(
+                    TaskId::new(),
+                    self.id,
+                    worker,
+                    requesting_account,
+                    expires,
+                    *task,
+                    Pending,
+                )
+            })
+            .collect();
+        Ok(out?)
+    }
+
+    fn authorize_account_assignment(
+        &self,
+        requesting_account: AccountId,
+        worker: AccountId,
+    ) -> Result<(), OrganizationError> {
+        //anyone may take on tasks themselves
+        if worker == requesting_account {
+            return self.authorize_member(requesting_account);
+        }
+
+        self.authorize(
+            requesting_account,
+            Capability::TaskAssign,
+            Scope::Organization,
+        )
+    }
+
+    pub fn add_repeating_task(
+        &self,
+        requesting_account: AccountId,
+        assigned_to: AssignmentType,
+        tasks: Vec<CatalogueTaskId>,
+        period_days: u64,
+        starts: DateTime<Utc>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        match &assigned_to {
+            AssignmentType::Account(worker) => {
+                self.authorize_account_assignment(requesting_account, *worker)?
+            }
+            AssignmentType::Tags { tags, .. } => self.authorize(
+                requesting_account,
+                Capability::TaskAssign,
+                Scope::Tags(tags),
+            )?,
+        };
+        if period_days == 0 || tasks.is_empty() {
+            return Err(OrganizationError::InvalidRepeatingTask);
+        }
+        self.settings
+            .sub_days(starts, Days::new(period_days))
+            .ok_or(OrganizationError::InvalidRepeatingTask)?;
+
+        Ok(OrganizationEvent::RepeatingTaskAdded {
+            id: RepeatingTaskId::new(),
+            requesting_account,
+            assigned_to,
+            tasks,
+            period_days,
+            starts,
+        })
+    }
+
+    pub fn has_due_repeats(&self, now: DateTime<Utc>) -> bool {
+        !self.due_repeats(now).is_empty()
+    }
+
+    pub fn due_repeats(&self, now: DateTime<Utc>) -> Vec<RepeatingTaskId> {
+        if self.archived.is_some() {
+            return Vec::new();
+        }
+        self.repeating_tasks
+            .iter()
+            .filter(|repeat| {
+                repeat
+                    .next_due(&self.settings)
+                    .is_some_and(|due| due <= now)
+            })
+            .map(|repeat| repeat.id)
+            .collect()
+    }
+
+    //assigns the latest missed occurrence only, skipped periods are not made up for
+    pub fn assign_repeating_task(
+        &self,
+        id: RepeatingTaskId,
+        now: DateTime<Utc>,
+        rng: &mut impl RngCore,
+    ) -> Result<Assignment, OrganizationError> {
+        self.ensure_active()?;
+        let repeat = self
+            .repeating_tasks
+            .iter()
+            .find(|repeat| repeat.id == id)
+            .ok_or(OrganizationError::InvalidRepeatingTask)?;
+
+        let mut scheduled = repeat
+            .next_due(&self.settings)
+            .ok_or(OrganizationError::InvalidRepeatingTask)?;
+        while let Some(next) = self.settings.add_days(scheduled, repeat.period) {
+            if next > now {
+                break;
+            }
+            scheduled = next;
+        }
+        //an occurrence stays open until the next one is handed out
+        let expires = self.settings.add_days(scheduled, repeat.period);
+
+        let mut assignment = match &repeat.assigned_to {
+            AssignmentType::Account(worker) => Assignment {
+                tasks: self.assign_tasks_to_account(
+                    repeat.requesting_account,
+                    *worker,
+                    &repeat.tasks,
+                    now,
+                    expires,
+                )?,
+                events: Vec::new(),
+                ineligible: Vec::new(),
+            },
+            AssignmentType::Tags {
+                tags,
+                assignment_type,
+            } => self.assign_tasks_to_tags(
+                &repeat.requesting_account,
+                tags,
+                &repeat.tasks,
+                assignment_type,
+                now,
+                expires,
+                rng,
+            )?,
+        };
+
+        assignment
+            .events
+            .push(OrganizationEvent::RepeatingTaskAssigned {
+                id,
+                assigned: scheduled,
+            });
+        Ok(assignment)
+    }
+
+    //whoever assigned the task may always give more time
+    pub fn add_time_to_task(
+        &self,
+        requesting_account: AccountId,
+        task: &TaskInstance,
+        time: Duration,
+    ) -> Result<TaskEvent, OrganizationError> {
+        self.ensure_active()?;
+        if requesting_account != task.assigned_by() {
+            let scope = match task.tags().is_empty() {
+                true => Scope::Organization,
+                false => Scope::Tags(task.tags()),
+            };
+            self.authorize(requesting_account, Capability::TaskAddTime, scope)?;
+        }
+
+        Ok(task.add_time(time)?)
+    }
+
+    pub fn authorize_catalogue_write(
+        &self,
+        requesting_account: AccountId,
+    ) -> Result<(), OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::CatalogueWrite,
+            Scope::Organization,
+        )
+    }
+
+    pub fn change_settings(
+        &self,
+        requesting_account: AccountId,
+        settings: OrganizationSettings,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::OrganizationConfigure,
+            Scope::Organization,
+        )?;
+        settings.validate()?;
+
+        Ok(OrganizationEvent::SettingsChanged {
+            settings,
+            changed_by: requesting_account,
+        })
+    }
+
+    pub fn set_reassignment_policy(
+        &self,
+        requesting_account: AccountId,
+        policy: Option<ReassignmentPolicy>,
+    ) -> Result<OrganizationEvent, OrganizationError> {
+        self.ensure_active()?;
+        self.authorize(
+            requesting_account,
+            Capability::OrganizationConfigure,
+            Scope::Organization,
+        )?;
+
+        Ok(OrganizationEvent::ReassignmentPolicySet { policy })
+    }
+
+    //None when the organization has no policy, an empty list when the task could not be handed on
+    pub fn reassign(
+        &self,
+        task: &TaskInstance,
+        now: DateTime<Utc>,
+        rng: &mut impl RngCore,
+    ) -> Result<Option<Assignment>, OrganizationError> {
+        let Some(policy) = &self.reassignment_policy else {
+            return Ok(None);
+        };
+        if self.archived.is_some() {
+            return Ok(None);
+        }
+        if task.reassignments() >= policy.max_reassignments || task.tags().is_empty() {
+            return Ok(Some(Assignment::default()));
+        }
+
+        let workers: Vec<AccountId> = self
+            .workers_in_tags(task.tags())
+            .into_iter()
+            .filter(|worker| *worker != task.assigned_to())
+            .collect();
+        //without its own expiry a policy falls back to the organization default
+        let expires = policy
+            .expires_after
+            .map(|duration| now + duration)
+            .or(self.settings.default_expires(now));
+
+        let mut rotations = self.rotations.clone();
+        match self.assign_to_workers(
+            &task.assigned_by(),
+            &workers,
+            task.tags(),
+            &[task.catalogue_id()],
+            &policy.assignment_type,
+            now,
+            expires,
+            &mut rotations,
+            rng,
+        ) {
+            Ok(tasks) => Ok(Some(Assignment {
+                tasks: tasks
+                    .into_iter()
+                    .map(|reassigned| reassigned.as_reassignment_of(task))
+                    .collect(),
+                events: self.rotation_events(rotations),
+                ineligible: Vec::new(),
+            })),
+            Err(
+                OrganizationError::NoWorkers
+                | OrganizationError::Unavailable
+                | OrganizationError::Ineligible(_)
+                | OrganizationError::CapReached,
+            ) => Ok(Some(Assignment::default())),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn apply(mut self, event: &OrganizationEvent) -> Self {
+        match event {
+            OrganizationEvent::Created { id, name } => {
+                self.id = *id;
+                self.name = name.clone();
+            }
+            OrganizationEvent::TagAdded {
+                organization_id: _,
+                tag_id,
+                name,
+            } => self.tags.push(Tag::new(
+                *tag_id,
+                name.clone(),
+                HashSet::new(),
+                HashSet::new(),
+            )),
+            OrganizationEvent::EditorAddedToTag { tag_id, account } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.authorized_editors.insert(*account);
+                }
+            }
+            OrganizationEvent::WorkerAddedToTag { tag_id, account } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.workers.insert(*account);
+                }
+            }
+            OrganizationEvent::TagRenamed { tag_id, name } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.name = name.clone();
+                }
+            }
+            OrganizationEvent::EditorRemovedFromTag { tag_id, account } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.authorized_editors.remove(account);
+                }
+            }
+            OrganizationEvent::WorkerRemovedFromTag { tag_id, account } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.workers.remove(account);
+                }
+            }
+            OrganizationEvent::TagParentSet { tag_id, parent } => {
+                if let Some(tag) = self.tags.iter_mut().find(|tag| tag.id == *tag_id) {
+                    tag.parent = *parent;
+                }
+            }
+            OrganizationEvent::TagRemoverd { tag } => {
+                self.tags.retain(|existing| existing.id != *tag);
+                self.caps.remove(&CapTarget::Tag(*tag));
+            }
+            OrganizationEvent::AccountLinked {
+                account,
+                account_type,
+            } => match self
+                .linked_accounts
+                .iter_mut()
+                .find(|link| link.account == *account)
+            {
+                Some(link) => link.account_type = *account_type,
+                None => {
+                    self.linked_accounts
+                        .push(AccountLink::new(*account, *account_type, Vec::new()))
+                }
+            },
+            OrganizationEvent::RoleChanged {
+                account,
+                from: _,
+                to,
+                changed_by: _,
+            } => {
+                if let Some(link) = self
+                    .linked_accounts
+                    .iter_mut()
+                    .find(|link| link.account == *account)
+                {
+                    link.account_type = *to;
+                }
+            }
+            OrganizationEvent::TradeProposed {
+                id,
+                proposed_by,
+                counterparty,
+                offered,
+                requested,
+                approvers,
+            } => self.trades.push(Trade {
+                id: *id,
+                proposed_by: *proposed_by,
+                counterparty: *counterparty,
+                offered: *offered,
+                requested: *requested,
+                accepted: false,
+                approvers: approvers.clone(),
+                approved_by: Default::default(),
+            }),
+            OrganizationEvent::TradeAccepted { id } => {
+                if let Some(trade) = self.trades.iter_mut().find(|trade| trade.id == *id) {
+                    trade.accepted = true;
+                }
+            }
+            OrganizationEvent::TradeApproved { id, approved_by } => {
+                if let Some(trade) = self.trades.iter_mut().find(|trade| trade.id == *id) {
+                    trade.approved_by.insert(*approved_by);
+                }
+            }
+            OrganizationEvent::TradeDeclined { id, declined_by: _ }
+            | OrganizationEvent::TradeCompleted { id } => {
+                self.trades.retain(|trade| trade.id != *id)
+            }
+            OrganizationEvent::WorkloadCapsSet {
+                target,
+                caps,
+                set_by: _,
+            } => {
+                match *caps == WorkloadCaps::default() {
+                    true => self.caps.remove(target),
+                    false => self.caps.insert(*target, *caps),
+                };
+            }
+            OrganizationEvent::ProfileSet {
+                account,
+                birthdate,
+                skills,
+                set_by: _,
+            } => {
+                if let Some(link) = self
+                    .linked_accounts
+                    .iter_mut()
+                    .find(|link| link.account == *account)
+                {
+                    link.birthdate = *birthdate;
+                    link.skills = skills.clone();
+                }
+            }
+            OrganizationEvent::HandicapSet {
+                account,
+                share,
+                set_by: _,
+            } => {
+                if let Some(link) = self
+                    .linked_accounts
+                    .iter_mut()
+                    .find(|link| link.account == *account)
+                {
+                    link.share = *share;
+                }
+            }
+            OrganizationEvent::UnavailabilityAdded {
+                id,
+                account,
+                unavailability,
+                added_by: _,
+            } => self.unavailability.push(AccountUnavailability {
+                id: *id,
+                account: *account,
+                unavailability: *unavailability,
+            }),
+            OrganizationEvent::UnavailabilityRemoved { id, removed_by: _ } => {
+                self.unavailability.retain(|period| period.id != *id)
+            }
+            OrganizationEvent::AccountUnlinked { account } => {
+                self.linked_accounts.retain(|link| link.account != *account);
+                self.unavailability
+                    .retain(|period| period.account != *account);
+                self.caps.remove(&CapTarget::Account(*account));
+                if self.ownership_offer == Some(*account) {
+                    self.ownership_offer = None;
+                }
+            }
+            OrganizationEvent::InvitationCreated {
+                id,
+                code,
+                account_type,
+                tags,
+                max_uses,
+                expires,
+                created_by,
+            } => self.invitations.push(Invitation {
+                id: *id,
+                code: code.clone(),
+                account_type: *account_type,
+                tags: tags.clone(),
+                max_uses: *max_uses,
+                uses: 0,
+                expires: *expires,
+                created_by: *created_by,
+                revoked: false,
+            }),
+            OrganizationEvent::InvitationRedeemed { id, account: _ } => {
+                if let Some(invitation) = self
+                    .invitations
+                    .iter_mut()
+                    .find(|invitation| invitation.id == *id)
+                {
+                    invitation.uses += 1;
+                }
+            }
+            OrganizationEvent::InvitationRevoked { id, revoked_by: _ } => {
+                if let Some(invitation) = self
+                    .invitations
+                    .iter_mut()
+                    .find(|invitation| invitation.id == *id)
+                {
+                    invitation.revoked = true;
+                }
+            }
+            OrganizationEvent::RoleCapabilitiesSet {
+                account_type,
+                capabilities,
+            } => self.policy.set(*account_type, capabilities.clone()),
+            OrganizationEvent::SettingsChanged {
+                settings,
+                changed_by: _,
+            } => self.settings = settings.clone(),
+            OrganizationEvent::RotationAdvanced { key, last } => {
+                self.rotations.set(key.clone(), *last)
+            }
+            OrganizationEvent::Archived { archived_by: _, at } => self.archived = Some(*at),
+            OrganizationEvent::Unarchived { unarchived_by: _ } => self.archived = None,
+            OrganizationEvent::ReassignmentPolicySet { policy } => {
+                self.reassignment_policy = policy.clone()
+            }
+            OrganizationEvent::RepeatingTaskAdded {
+                id,
+                requesting_account,
+                assigned_to,
+                tasks,
+                period_days,
+                starts,
+            } => {
+                let period = Days::new(*period_days);
+                if let Some(last_assigned) = self.settings.sub_days(*starts, period) {
+                    self.repeating_tasks.push(RepeatingTask {
+                        id: *id,
+                        last_assigned,
+                        requesting_account: *requesting_account,
+                        period,
+                        assigned_to: assigned_to.clone(),
+                        tasks: tasks.clone(),
+                    });
+                }
+            }
+            OrganizationEvent::OwnershipOffered { from: _, to } => self.ownership_offer = Some(*to),
+            OrganizationEvent::OwnershipDeclined { account: _ } => self.ownership_offer = None,
+            //both role changes happen in one event so there is never more or less than one owner
+            OrganizationEvent::OwnershipTransferred { from, to } => {
+                self.ownership_offer = None;
+                for link in self.linked_accounts.iter_mut() {
+                    if link.account == *from {
+                        link.account_type = AccountType::Admin;
+                    } else if link.account == *to {
+                        link.account_type = AccountType::Owner;
+                    }
+                }
+            }
+            OrganizationEvent::RepeatingTaskRemoved { id } => {
+                self.repeating_tasks.retain(|repeat| repeat.id != *id)
+            }
+            OrganizationEvent::RepeatingTaskAssigned { id, assigned } => {
+                if let Some(repeat) = self
+                    .repeating_tasks
+                    .iter_mut()
+                    .find(|repeat| repeat.id == *id)
+                {
+                    repeat.last_assigned = *assigned;
                 }
             }
+        };
+
+        self
+    }
+
+    //pending tasks live in the task streams, repositories attach them after replaying org events
+    pub fn with_pending_task(mut self, account: AccountId, task: TaskId) -> Self {
+        match self
+            .linked_accounts
+            .iter_mut()
+            .find(|link| link.account == account)
+        {
+            Some(link) => link.tasks.push(task),
+            None => self.orphaned_tasks.push(task),
         }
+        self
     }
 
-    pub fn assign_tasks_to_account(
-        &self,
-        requesting_account: AccountId,
-        worker: AccountId,
-        tasks: &Vec<CatalogueTaskId>,
-    ) -> Result<Vec<TaskInstance>, OrganizationError> {
-        let link = self
+    pub fn with_effort(mut self, account: AccountId, effort: u32) -> Self {
+        if let Some(link) = self
             .linked_accounts
-            .iter()
-            .find(|&link| link.account == requesting_account)
-            .ok_or(OrganizationError::NotInOrg)?;
+            .iter_mut()
+            .find(|link| link.account == account)
+        {
+            link.effort = effort;
+        }
+        self
+    }
 
-        if worker == requesting_account || link.account_type != AccountType::Worker {
-            let out: Result<Vec<TaskInstance>, TaskDomainError> = tasks
-                .iter()
-                .map(|task| {
-                    TaskInstance::new(
-                        TaskId::new(),
-                        worker,
-                        requesting_account,
-                        None,
-                        *task,
-                        Pending,
-                    )
-                })
-                .collect();
-            return Ok(out?);
+    pub fn with_workload(mut self, account: AccountId, workload: Workload) -> Self {
+        if let Some(link) = self
+            .linked_accounts
+            .iter_mut()
+            .find(|link| link.account == account)
+        {
+            link.workload = workload;
         }
+        self
+    }
+
+    pub fn with_catalogue_effort(mut self, task: CatalogueTaskId, effort: u32) -> Self {
+        self.catalogue_effort.insert(task, effort);
+        self
+    }
+
+    pub fn with_catalogue_requirements(
+        mut self,
+        task: CatalogueTaskId,
+        requirements: Requirements,
+    ) -> Self {
+        self.catalogue_requirements.insert(task, requirements);
+        self
+    }
 
-        Err(OrganizationError::NotAuthorized)
+    pub fn effort_of(&self, task: &CatalogueTaskId) -> u32 {
+        self.catalogue_effort
+            .get(task)
+            .copied()
+            .unwrap_or(DEFAULT_EFFORT)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Ord, Default, Deserialize, Serialize,
+)]
 pub struct TagId(pub Ulid);
 
 impl TagId {
     pub fn new() -> TagId {
         TagId(Ulid::new())
     }
+
+    pub fn ulid(&self) -> Ulid {
+        self.0
+    }
+}
+
+impl From<Uuid> for TagId {
+    fn from(value: Uuid) -> Self {
+        Self(value.into())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -439,6 +2712,7 @@ pub struct Tag {
     name: String,
     authorized_editors: HashSet<AccountId>,
     workers: HashSet<AccountId>,
+    parent: Option<TagId>,
 }
 
 impl Tag {
@@ -453,8 +2727,13 @@ impl Tag {
             name,
             authorized_editors,
             workers,
+            parent: None,
         }
     }
+
+    pub fn parent(&self) -> Option<TagId> {
+        self.parent
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
@@ -462,6 +2741,14 @@ pub struct AccountLink {
     account: AccountId,
     account_type: AccountType,
     tasks: Vec<TaskId>,
+    //percent of a full share of effort, lower for accounts that should do less
+    share: u32,
+    //effort assigned within the organization's effort window
+    effort: u32,
+    birthdate: Option<NaiveDate>,
+    skills: BTreeSet<String>,
+    //tasks and effort the account has due by day, see Workload
+    workload: Workload,
 }
 
 impl AccountLink {
@@ -470,20 +2757,97 @@ impl AccountLink {
             account,
             account_type,
             tasks,
+            share: FULL_SHARE,
+            effort: 0,
+            birthdate: None,
+            skills: BTreeSet::new(),
+            workload: Workload::default(),
         }
     }
+
+    pub fn account(&self) -> AccountId {
+        self.account
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+
+    pub fn tasks(&self) -> &[TaskId] {
+        &self.tasks
+    }
+
+    pub fn share(&self) -> u32 {
+        self.share
+    }
+
+    pub fn effort(&self) -> u32 {
+        self.effort
+    }
+
+    pub fn birthdate(&self) -> Option<NaiveDate> {
+        self.birthdate
+    }
+
+    pub fn skills(&self) -> &BTreeSet<String> {
+        &self.skills
+    }
+
+    pub fn workload(&self) -> &Workload {
+        &self.workload
+    }
+
+    //effort scaled up by the handicap, so someone with half a share counts double
+    fn weighted_effort(&self) -> u64 {
+        u64::from(self.effort) * u64::from(FULL_SHARE) / u64::from(self.share.max(1))
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash)]
+pub const FULL_SHARE: u32 = 100;
+
+//ordered by rank, worker lowest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash, Deserialize, Serialize)]
 pub enum AccountType {
     Worker,
     Admin,
     Owner,
 }
 
+impl AccountType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::Worker => "worker",
+            AccountType::Admin => "admin",
+            AccountType::Owner => "owner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<AccountType> {
+        match value {
+            "worker" => Some(AccountType::Worker),
+            "admin" => Some(AccountType::Admin),
+            "owner" => Some(AccountType::Owner),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Default, Deserialize, Serialize)]
+pub struct RepeatingTaskId(pub Ulid);
+
+impl RepeatingTaskId {
+    pub fn new() -> RepeatingTaskId {
+        RepeatingTaskId(Ulid::new())
+    }
+
+    pub fn ulid(&self) -> Ulid {
+        self.0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RepeatingTask {
-    id: Ulid,
+    id: RepeatingTaskId,
     last_assigned: DateTime<Utc>,
     requesting_account: AccountId,
     period: Days,
@@ -491,14 +2855,20 @@ pub struct RepeatingTask {
     tasks: Vec<CatalogueTaskId>,
 }
 
+impl RepeatingTask {
+    fn next_due(&self, settings: &OrganizationSettings) -> Option<DateTime<Utc>> {
+        settings.add_days(self.last_assigned, self.period)
+    }
+}
+
 impl PartialEq for RepeatingTask {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
     }
 }
 
-#[derive(Debug, Clone)]
-enum AssignmentType {
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum AssignmentType {
     Account(AccountId),
     Tags {
         tags: HashSet<TagId>,
@@ -510,6 +2880,8 @@ enum AssignmentType {
 pub enum OrganizationError {
     #[error("cannot create organization")]
     CannotCreate,
+    #[error("organization does not exist")]
+    OrganizationDoesNotExist,
     #[error("tag already exists")]
     TagAlreadyExists,
     #[error("tag does not exist")]
@@ -518,19 +2890,1176 @@ pub enum OrganizationError {
     NoWorkers,
     #[error("error creating task")]
     TaskError(#[from] TaskDomainError),
-    #[error("not authorized for tags")]
+    #[error("not authorized for this action")]
     NotAuthorized,
     #[error("requesting account is not part of this organization")]
     NotInOrg,
     #[error("repeating task date is invalid")]
     InvalidRepeatingTask,
+    #[error("ownership can only be offered to another account in the organization")]
+    InvalidOwnershipTransfer,
+    #[error("account has no open ownership offer")]
+    NoOwnershipOffer,
+    #[error("account is not part of this tag")]
+    NotInTag,
+    #[error("the owner cannot leave the organization, transfer ownership first")]
+    OwnerCannotLeave,
+    #[error("account is already linked to this organization")]
+    AccountAlreadyLinked,
+    #[error("role can only change between worker and admin")]
+    InvalidRoleChange,
+    #[error("invitation does not exist")]
+    InvitationDoesNotExist,
+    #[error("invitation was revoked, has expired or was used up")]
+    InvitationUnavailable,
+    #[error("organization is archived and read only")]
+    Archived,
+    #[error("organization is not archived")]
+    NotArchived,
+    #[error("a tag cannot be its own ancestor")]
+    TagCycle,
+    #[error("unknown locale, expected a name like en_US")]
+    UnknownLocale,
+    #[error("default expiry must be in the future")]
+    InvalidDefaultExpiry,
+    #[error("effort window must be at least one day")]
+    InvalidEffortWindow,
+    #[error("share must be more than zero percent")]
+    InvalidShare,
+    #[error("everyone who could take the task is unavailable until it is due")]
+    Unavailable,
+    #[error("unavailability must end after it starts")]
+    InvalidUnavailability,
+    #[error("unavailability does not exist")]
+    UnavailabilityDoesNotExist,
+    #[error("no worker meets the requirements: {}", explain(.0))]
+    Ineligible(Vec<Ineligibility>),
+    #[error("workload caps leave nobody to take the task")]
+    CapReached,
+    #[error("the week has no room for every task within availability and caps")]
+    CannotPlan,
+    #[error("trade does not exist")]
+    TradeDoesNotExist,
+    #[error("trades need a pending task of each side and another account of the organization")]
+    InvalidTrade,
+    #[error("task is already part of another trade")]
+    TaskAlreadyTraded,
+    #[error("random pick does not replay from its seed and candidates")]
+    RandomPickMismatch,
 }
 
-#[derive(Debug, Clone)]
+//what happens to the pending tasks of an account that leaves or is unlinked
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum PendingTaskHandling {
+    Cancel,
+    //tasks assigned through tags go to the remaining workers, anything else is left orphaned
+    Reassign(TaskAssignmentType),
+    //tasks stay with the account until an owner or admin reviews them
+    Orphan,
+}
+
+//what happens to expired and rejected tasks that were assigned through tags
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ReassignmentPolicy {
+    pub assignment_type: TaskAssignmentType,
+    pub max_reassignments: u32,
+    #[serde(default, with = "crate::shared::serde_duration::optional_seconds")]
+    pub expires_after: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum TaskAssignmentType {
     Random,
     Copy,
     LowestTasks,
     HighestTasks,
-    ToAccount { account: AccountId },
+    //balances the effort points assigned within the effort window, weighted by each account's share
+    LowestEffort,
+    ToAccount {
+        account: AccountId,
+    },
+    //takes turns, the organization remembers whose turn it was
+    RoundRobin {
+        #[serde(default)]
+        rotation: Rotation,
+    },
+}
+
+//the worker a random assignment picks for a seed, workers are sorted first so their order does not matter
+pub fn random_pick(workers: &[AccountId], seed: u64) -> Option<AccountId> {
+    let mut workers = workers.to_vec();
+    workers.sort();
+    workers
+        .choose(&mut ChaCha8Rng::seed_from_u64(seed))
+        .copied()
+}
+
+//tasks handed out by one assignment, with the organization events it caused
+#[derive(Debug, Clone, Default)]
+pub struct Assignment {
+    pub tasks: Vec<TaskInstance>,
+    pub events: Vec<OrganizationEvent>,
+    //workers of the tags that were left out of some of the tasks
+    pub ineligible: Vec<Ineligibility>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{eligibility::IneligibleReason, workload::DayLoad};
+    use super::*;
+
+    //a wednesday
+    fn now() -> DateTime<Utc> {
+        "2024-01-03T12:00:00Z".parse().unwrap()
+    }
+
+    struct Household {
+        org: Organization,
+        owner: AccountId,
+        workers: Vec<AccountId>,
+        tag: TagId,
+    }
+
+    //an owner and workers that all work in one tag
+    fn household(workers: usize) -> Household {
+        let owner = AccountId::new();
+        let mut workers: Vec<AccountId> = (0..workers).map(|_| AccountId::new()).collect();
+        workers.sort();
+        let tag = TagId::new();
+        let org = Organization::create("home".to_string(), owner).unwrap();
+        let mut events = org.clone().into_create_event().unwrap();
+        events.push(OrganizationEvent::TagAdded {
+            organization_id: *org.id(),
+            tag_id: tag,
+            name: "chores".to_string(),
+        });
+        for worker in &workers {
+            events.push(OrganizationEvent::AccountLinked {
+                account: *worker,
+                account_type: AccountType::Worker,
+            });
+            events.push(OrganizationEvent::WorkerAddedToTag {
+                tag_id: tag,
+                account: *worker,
+            });
+        }
+        Household {
+            org: events.iter().fold(org, |org, event| org.apply(event)),
+            owner,
+            workers,
+            tag,
+        }
+    }
+
+    impl Household {
+        fn pending(&self, account: AccountId) -> TaskInstance {
+            TaskInstance::new(
+                TaskId::new(),
+                *self.org.id(),
+                account,
+                self.owner,
+                None,
+                CatalogueTaskId::new(),
+                Pending,
+            )
+            .unwrap()
+        }
+
+        fn apply(mut self, event: &OrganizationEvent) -> Self {
+            self.org = self.org.apply(event);
+            self
+        }
+
+        fn assign(
+            &self,
+            tasks: usize,
+            assignment_type: TaskAssignmentType,
+            expires: Option<DateTime<Utc>>,
+        ) -> Result<Assignment, OrganizationError> {
+            let tasks: Vec<CatalogueTaskId> = (0..tasks).map(|_| CatalogueTaskId::new()).collect();
+            self.org.assign_tasks_to_tags(
+                &self.owner,
+                &HashSet::from([self.tag]),
+                &tasks,
+                &assignment_type,
+                now(),
+                expires,
+                &mut ChaCha8Rng::seed_from_u64(7),
+            )
+        }
+    }
+
+    fn assignees(assignment: &Assignment) -> Vec<AccountId> {
+        assignment
+            .tasks
+            .iter()
+            .map(|task| task.assigned_to())
+            .collect()
+    }
+    #[test]
+    fn ownership_goes_to_a_member_that_accepts() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        assert!(matches!(
+            home.org.transfer_ownership(a, b),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        assert!(matches!(
+            home.org.transfer_ownership(home.owner, home.owner),
+            Err(OrganizationError::InvalidOwnershipTransfer)
+        ));
+        assert!(matches!(
+            home.org.transfer_ownership(home.owner, AccountId::new()),
+            Err(OrganizationError::NotInOrg)
+        ));
+
+        let offered = home.org.transfer_ownership(home.owner, a).unwrap();
+        let home = home.apply(&offered);
+        assert_eq!(home.org.ownership_offer(), Some(a));
+        assert!(matches!(
+            home.org.accept_ownership(b),
+            Err(OrganizationError::NoOwnershipOffer)
+        ));
+        let accepted = home.org.accept_ownership(a).unwrap();
+        let home = home.apply(&accepted);
+        assert_eq!(home.org.owner(), Some(a));
+        assert_eq!(home.org.ownership_offer(), None);
+        //the previous owner stays on as an admin
+        assert!(home
+            .org
+            .linked_accounts
+            .iter()
+            .any(|link| link.account == home.owner && link.account_type == AccountType::Admin));
+    }
+
+    #[test]
+    fn declined_ownership_stays_with_the_owner() {
+        let home = household(1);
+        let worker = home.workers[0];
+        let offered = home.org.transfer_ownership(home.owner, worker).unwrap();
+        let home = home.apply(&offered);
+        let declined = home.org.decline_ownership(worker).unwrap();
+        let home = home.apply(&declined);
+        assert_eq!(home.org.owner(), Some(home.owner));
+        assert!(matches!(
+            home.org.accept_ownership(worker),
+            Err(OrganizationError::NoOwnershipOffer)
+        ));
+        assert!(matches!(
+            home.org.decline_ownership(worker),
+            Err(OrganizationError::NoOwnershipOffer)
+        ));
+    }
+
+    #[test]
+    fn unlinking_drops_tags_and_releases_pending_tasks() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        assert!(matches!(
+            home.org.unlink_account(a, home.owner),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        let events = home.org.unlink_account(home.owner, a).unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            OrganizationEvent::WorkerRemovedFromTag { tag_id, account }
+                if *tag_id == home.tag && *account == a
+        )));
+        let task = home.pending(a).with_tags(HashSet::from([home.tag]));
+        let home = events.iter().fold(home, |home, event| home.apply(event));
+        assert_eq!(
+            home.org.workers_in_tags(&HashSet::from([home.tag])),
+            vec![b]
+        );
+
+        let (released, _) = home
+            .org
+            .release_tasks(
+                home.owner,
+                std::slice::from_ref(&task),
+                &PendingTaskHandling::Reassign(TaskAssignmentType::LowestTasks),
+                now(),
+                &mut ChaCha8Rng::seed_from_u64(7),
+            )
+            .unwrap();
+        assert!(matches!(
+            released[0],
+            TaskEvent::Cancelled { task_id, .. } if task_id == task.id()
+        ));
+        assert!(matches!(
+            &released[1],
+            TaskEvent::Assigned { assigned_to, reassigned_from, .. }
+                if *assigned_to == b && *reassigned_from == Some(task.id())
+        ));
+
+        let release = |handling| {
+            home.org
+                .release_tasks(
+                    home.owner,
+                    std::slice::from_ref(&task),
+                    &handling,
+                    now(),
+                    &mut ChaCha8Rng::seed_from_u64(7),
+                )
+                .unwrap()
+                .0
+        };
+        assert_eq!(release(PendingTaskHandling::Cancel).len(), 1);
+        assert!(release(PendingTaskHandling::Orphan).is_empty());
+    }
+
+    #[test]
+    fn roles_only_change_below_your_own() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        //nobody can demote the owner, not even the owner
+        assert!(matches!(
+            home.org
+                .change_role(home.owner, home.owner, AccountType::Admin),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        assert!(matches!(
+            home.org.change_role(home.owner, a, AccountType::Owner),
+            Err(OrganizationError::InvalidRoleChange)
+        ));
+        assert!(matches!(
+            home.org.change_role(home.owner, a, AccountType::Worker),
+            Err(OrganizationError::InvalidRoleChange)
+        ));
+        assert!(matches!(
+            home.org
+                .change_role(home.owner, AccountId::new(), AccountType::Admin),
+            Err(OrganizationError::NotInOrg)
+        ));
+        assert!(home.org.change_role(a, b, AccountType::Admin).is_err());
+
+        let promoted = home
+            .org
+            .change_role(home.owner, a, AccountType::Admin)
+            .unwrap();
+        let home = home.apply(&promoted);
+        //an admin manages workers but cannot make more admins or touch the owner
+        assert!(matches!(
+            home.org.change_role(a, b, AccountType::Admin),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        assert!(matches!(
+            home.org.change_role(a, home.owner, AccountType::Worker),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        let demoted = home
+            .org
+            .change_role(home.owner, a, AccountType::Worker)
+            .unwrap();
+        let home = home.apply(&demoted);
+        assert!(home
+            .org
+            .linked_accounts
+            .iter()
+            .all(|link| link.account_type != AccountType::Admin));
+    }
+
+    impl Household {
+        fn invite(
+            self,
+            max_uses: Option<u32>,
+            expires: Option<DateTime<Utc>>,
+        ) -> (Self, InvitationId, String) {
+            let created = self
+                .org
+                .create_invitation(
+                    self.owner,
+                    AccountType::Worker,
+                    HashSet::from([self.tag]),
+                    max_uses,
+                    expires,
+                )
+                .unwrap();
+            let OrganizationEvent::InvitationCreated { id, code, .. } = &created else {
+                panic!("expected an invitation");
+            };
+            let (id, code) = (*id, code.clone());
+            (self.apply(&created), id, code)
+        }
+
+        fn redeem(self, code: &str, account: AccountId) -> Result<Self, OrganizationError> {
+            let events = self.org.redeem_invitation(code, account, now())?;
+            Ok(events.iter().fold(self, |home, event| home.apply(event)))
+        }
+    }
+
+    #[test]
+    fn invitations_link_the_account_into_the_tags() {
+        let (home, _, code) = household(1).invite(Some(1), None);
+        let joining = AccountId::new();
+        assert!(matches!(
+            home.org.redeem_invitation("WRONG", joining, now()),
+            Err(OrganizationError::InvitationDoesNotExist)
+        ));
+        assert!(matches!(
+            home.org.redeem_invitation(&code, home.workers[0], now()),
+            Err(OrganizationError::AccountAlreadyLinked)
+        ));
+
+        let home = home.redeem(&code, joining).unwrap();
+        assert!(home
+            .org
+            .workers_in_tags(&HashSet::from([home.tag]))
+            .contains(&joining));
+        //used up after a single use
+        assert!(matches!(
+            home.redeem(&code, AccountId::new()),
+            Err(OrganizationError::InvitationUnavailable)
+        ));
+    }
+
+    #[test]
+    fn expired_and_revoked_invitations_cannot_be_redeemed() {
+        let (home, _, expired) = household(1).invite(None, Some(now() - Duration::hours(1)));
+        assert!(matches!(
+            home.org
+                .redeem_invitation(&expired, AccountId::new(), now()),
+            Err(OrganizationError::InvitationUnavailable)
+        ));
+
+        let (home, id, code) = home.invite(None, Some(now() + Duration::hours(1)));
+        let revoked = home.org.revoke_invitation(home.owner, id).unwrap();
+        let home = home.apply(&revoked);
+        assert!(matches!(
+            home.org.redeem_invitation(&code, AccountId::new(), now()),
+            Err(OrganizationError::InvitationUnavailable)
+        ));
+        assert!(matches!(
+            home.org.revoke_invitation(home.owner, id),
+            Err(OrganizationError::InvitationUnavailable)
+        ));
+    }
+
+    #[test]
+    fn invitations_hand_out_roles_below_their_creator() {
+        let home = household(1);
+        let worker = home.workers[0];
+        let invite = |account, account_type, max_uses| {
+            home.org
+                .create_invitation(account, account_type, HashSet::new(), max_uses, None)
+        };
+        assert!(matches!(
+            invite(home.owner, AccountType::Owner, None),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        assert!(matches!(
+            invite(home.owner, AccountType::Worker, Some(0)),
+            Err(OrganizationError::InvitationUnavailable)
+        ));
+        assert!(invite(worker, AccountType::Worker, None).is_err());
+        assert!(invite(home.owner, AccountType::Admin, None).is_ok());
+    }
+
+    #[test]
+    fn only_linked_accounts_join_tags() {
+        let home = household(1);
+        assert!(matches!(
+            home.org
+                .add_worker_to_tag(home.tag, home.owner, AccountId::new()),
+            Err(OrganizationError::NotInOrg)
+        ));
+        assert!(home
+            .org
+            .add_worker_to_tag(home.tag, home.owner, home.owner)
+            .is_ok());
+    }
+
+    #[test]
+    fn task_counts_pass_over_tag_workers_that_left() {
+        let home = household(2);
+        //written before tags checked for linked accounts
+        let added = OrganizationEvent::WorkerAddedToTag {
+            tag_id: home.tag,
+            account: AccountId::new(),
+        };
+        let home = home.apply(&added);
+        for assignment_type in [
+            TaskAssignmentType::LowestTasks,
+            TaskAssignmentType::HighestTasks,
+        ] {
+            let assigned = home.assign(3, assignment_type, None).unwrap();
+            assert!(assignees(&assigned)
+                .iter()
+                .all(|worker| home.workers.contains(worker)));
+        }
+    }
+
+    impl Household {
+        fn add_tag(self, name: &str) -> (Self, TagId) {
+            let events = self.org.add_tag(name.to_string(), self.owner).unwrap();
+            let Some(OrganizationEvent::TagAdded { tag_id, .. }) = events.first() else {
+                panic!("expected a tag");
+            };
+            let tag_id = *tag_id;
+            (
+                events.iter().fold(self, |home, event| home.apply(event)),
+                tag_id,
+            )
+        }
+
+        fn set_parent(self, tag: TagId, parent: TagId) -> Self {
+            let set = self
+                .org
+                .set_tag_parent(tag, self.owner, Some(parent))
+                .unwrap();
+            self.apply(&set)
+        }
+    }
+
+    #[test]
+    fn tags_cannot_become_their_own_ancestors() {
+        let (home, kitchen) = household(1).add_tag("kitchen");
+        let (home, dishes) = home.add_tag("dishes");
+        let root = home.tag;
+        let home = home.set_parent(kitchen, root).set_parent(dishes, kitchen);
+        for (tag, parent) in [(home.tag, dishes), (home.tag, kitchen), (dishes, dishes)] {
+            assert!(matches!(
+                home.org.set_tag_parent(tag, home.owner, Some(parent)),
+                Err(OrganizationError::TagCycle)
+            ));
+        }
+        assert!(home.org.set_tag_parent(dishes, home.owner, None).is_ok());
+        assert!(matches!(
+            home.org
+                .set_tag_parent(dishes, home.owner, Some(TagId::new())),
+            Err(OrganizationError::TagDoesNotExist)
+        ));
+    }
+
+    #[test]
+    fn workers_of_a_tag_work_in_every_tag_below_it() {
+        let (home, kitchen) = household(1).add_tag("kitchen");
+        let (home, dishes) = home.add_tag("dishes");
+        let worker = home.workers[0];
+        assert!(home.org.workers_of_tag(dishes).unwrap().is_empty());
+
+        let root = home.tag;
+        let home = home.set_parent(kitchen, root).set_parent(dishes, kitchen);
+        assert_eq!(
+            home.org.workers_of_tag(dishes).unwrap(),
+            HashSet::from([worker])
+        );
+        assert_eq!(
+            home.org.workers_in_tags(&HashSet::from([dishes, kitchen])),
+            vec![worker]
+        );
+        //but not in the tags above it
+        let added = OrganizationEvent::WorkerAddedToTag {
+            tag_id: dishes,
+            account: home.owner,
+        };
+        let home = home.apply(&added);
+        assert!(!home
+            .org
+            .workers_of_tag(home.tag)
+            .unwrap()
+            .contains(&home.owner));
+    }
+
+    impl Household {
+        fn confirm(
+            &self,
+            proposed: &[TaskInstance],
+            assignment_type: TaskAssignmentType,
+        ) -> Result<Assignment, OrganizationError> {
+            self.org.confirm_assignment(
+                &self.owner,
+                &HashSet::from([self.tag]),
+                proposed,
+                &assignment_type,
+                now(),
+            )
+        }
+    }
+
+    #[test]
+    fn previews_can_be_edited_before_they_are_confirmed() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        let preview = home
+            .assign(1, TaskAssignmentType::ToAccount { account: a }, None)
+            .unwrap();
+        let confirmed = home
+            .confirm(&preview.tasks, TaskAssignmentType::LowestTasks)
+            .unwrap();
+        assert_eq!(assignees(&confirmed), vec![a]);
+        //confirmed tasks are new tasks, the preview itself was never written
+        assert_ne!(confirmed.tasks[0].id(), preview.tasks[0].id());
+
+        let edited = vec![preview.tasks[0].clone().with_assigned_to(b)];
+        let confirmed = home
+            .confirm(
+                &edited,
+                TaskAssignmentType::RoundRobin {
+                    rotation: Rotation::Tags,
+                },
+            )
+            .unwrap();
+        assert_eq!(assignees(&confirmed), vec![b]);
+        //a round robin carries on from whoever ended up with the task
+        assert!(matches!(
+            confirmed.events.as_slice(),
+            [OrganizationEvent::RotationAdvanced { last, .. }] if *last == b
+        ));
+    }
+
+    #[test]
+    fn confirming_checks_the_organization_as_it_is_now() {
+        let home = household(2);
+        let a = home.workers[0];
+        let preview = home
+            .assign(1, TaskAssignmentType::ToAccount { account: a }, None)
+            .unwrap();
+        let changed = |event: OrganizationEvent| home.org.clone().apply(&event);
+
+        let left = changed(
+            home.org
+                .remove_worker_from_tag(home.tag, home.owner, a)
+                .unwrap(),
+        );
+        assert!(matches!(
+            left.confirm_assignment(
+                &home.owner,
+                &HashSet::from([home.tag]),
+                &preview.tasks,
+                &TaskAssignmentType::LowestTasks,
+                now(),
+            ),
+            Err(OrganizationError::NotInTag)
+        ));
+
+        let away = changed(OrganizationEvent::UnavailabilityAdded {
+            id: UnavailabilityId::new(),
+            account: a,
+            unavailability: Unavailability::Away {
+                from: now() - Duration::hours(1),
+                until: now() + Duration::hours(1),
+            },
+            added_by: a,
+        });
+        assert!(matches!(
+            away.confirm_assignment(
+                &home.owner,
+                &HashSet::from([home.tag]),
+                &preview.tasks,
+                &TaskAssignmentType::LowestTasks,
+                now(),
+            ),
+            Err(OrganizationError::Unavailable)
+        ));
+    }
+
+    #[test]
+    fn random_picks_replay_from_their_seed() {
+        let mut workers: Vec<AccountId> = (0..5).map(|_| AccountId::new()).collect();
+        let picked = random_pick(&workers, 42);
+        workers.reverse();
+        assert_eq!(random_pick(&workers, 42), picked);
+        assert!(picked.is_some_and(|picked| workers.contains(&picked)));
+        assert_eq!(random_pick(&[], 42), None);
+    }
+
+    #[test]
+    fn confirmed_random_picks_have_to_replay() {
+        let home = household(3);
+        let preview = home.assign(1, TaskAssignmentType::Random, None).unwrap();
+        let task = &preview.tasks[0];
+        let seed = task.seed().unwrap();
+        assert_eq!(task.candidates(), home.workers.as_slice());
+        assert_eq!(
+            random_pick(task.candidates(), seed),
+            Some(task.assigned_to())
+        );
+
+        let confirmed = home
+            .confirm(&preview.tasks, TaskAssignmentType::Random)
+            .unwrap();
+        assert_eq!(confirmed.tasks[0].seed(), Some(seed));
+        assert_eq!(confirmed.tasks[0].candidates(), task.candidates());
+
+        //someone else would need a different seed
+        let other = *home
+            .workers
+            .iter()
+            .find(|worker| **worker != task.assigned_to())
+            .unwrap();
+        let tampered = task
+            .clone()
+            .with_assigned_to(other)
+            .with_random_pick(seed, task.candidates().to_vec());
+        assert!(matches!(
+            home.confirm(&[tampered], TaskAssignmentType::Random),
+            Err(OrganizationError::RandomPickMismatch)
+        ));
+        //and a pick among workers that have since left the tags is not replayed
+        let left = home
+            .org
+            .remove_worker_from_tag(home.tag, home.owner, other)
+            .unwrap();
+        let home = home.apply(&left);
+        assert!(matches!(
+            home.confirm(&preview.tasks, TaskAssignmentType::Random),
+            Err(OrganizationError::RandomPickMismatch)
+        ));
+    }
+
+    impl Household {
+        fn post(&self, claim_deadline: Option<ClaimDeadline>) -> TaskInstance {
+            let posted = self
+                .org
+                .post_tasks(
+                    self.owner,
+                    &HashSet::from([self.tag]),
+                    &[CatalogueTaskId::new()],
+                    Some(now() + Duration::days(1)),
+                    claim_deadline,
+                )
+                .unwrap();
+            posted.into_iter().next().unwrap()
+        }
+    }
+
+    #[test]
+    fn posted_tasks_are_open_to_the_tags() {
+        let home = household(2);
+        let task = home.post(None);
+        assert_eq!(*task.status(), TaskStatus::Open);
+        assert_eq!(*task.tags(), HashSet::from([home.tag]));
+
+        let empty = household(0);
+        assert!(matches!(
+            empty.org.post_tasks(
+                empty.owner,
+                &HashSet::from([empty.tag]),
+                &[CatalogueTaskId::new()],
+                None,
+                None,
+            ),
+            Err(OrganizationError::NoWorkers)
+        ));
+    }
+
+    #[test]
+    fn the_first_claim_wins() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        let task = home.post(None);
+        let claimed = task
+            .clone()
+            .apply(&home.org.claim_task(a, &task, now()).unwrap());
+        assert_eq!(*claimed.status(), Pending);
+        assert_eq!(claimed.assigned_to(), a);
+        assert!(matches!(
+            home.org.claim_task(b, &claimed, now()),
+            Err(OrganizationError::TaskError(
+                TaskDomainError::AlreadyClaimed
+            ))
+        ));
+
+        //only the claimer can put it back
+        assert!(home.org.unclaim_task(b, &claimed).is_err());
+        let unclaimed = claimed
+            .clone()
+            .apply(&home.org.unclaim_task(a, &claimed).unwrap());
+        assert_eq!(*unclaimed.status(), TaskStatus::Open);
+        assert!(home.org.claim_task(b, &unclaimed, now()).is_ok());
+    }
+
+    #[test]
+    fn claims_are_limited_to_the_tags() {
+        let home = household(1);
+        let outsider = AccountId::new();
+        let home = home.apply(&OrganizationEvent::AccountLinked {
+            account: outsider,
+            account_type: AccountType::Worker,
+        });
+        let task = home.post(None);
+        assert!(matches!(
+            home.org.claim_task(outsider, &task, now()),
+            Err(OrganizationError::NotInTag)
+        ));
+    }
+
+    #[test]
+    fn away_workers_cannot_claim() {
+        let home = household(1);
+        let worker = home.workers[0];
+        let away = OrganizationEvent::UnavailabilityAdded {
+            id: UnavailabilityId::new(),
+            account: worker,
+            unavailability: Unavailability::Away {
+                from: now() - Duration::hours(1),
+                until: now() + Duration::days(2),
+            },
+            added_by: worker,
+        };
+        let task = home.post(None);
+        let home = home.apply(&away);
+        assert!(matches!(
+            home.org.claim_task(worker, &task, now()),
+            Err(OrganizationError::Unavailable)
+        ));
+        //back before the task is due
+        assert!(home
+            .org
+            .claim_task(worker, &task, now() + Duration::days(2))
+            .is_ok());
+    }
+
+    #[test]
+    fn unclaimed_tasks_fall_back_after_the_deadline() {
+        let home = household(2);
+        let b = home.workers[1];
+        let without = home.post(None);
+        assert!(home
+            .org
+            .fall_back(&without, now(), &mut ChaCha8Rng::seed_from_u64(7))
+            .is_err());
+
+        let task = home.post(Some(ClaimDeadline {
+            at: now(),
+            fallback: TaskAssignmentType::ToAccount { account: b },
+        }));
+        let (events, _) = home
+            .org
+            .fall_back(&task, now(), &mut ChaCha8Rng::seed_from_u64(7))
+            .unwrap();
+        assert!(matches!(
+            events[0],
+            TaskEvent::ClaimDeadlinePassed { task_id } if task_id == task.id()
+        ));
+        let TaskEvent::Assigned {
+            assigned_to,
+            reassigned_from,
+            tags,
+            ..
+        } = &events[1]
+        else {
+            panic!("expected an assignment");
+        };
+        assert_eq!(*assigned_to, b);
+        assert_eq!(*reassigned_from, Some(task.id()));
+        assert_eq!(*tags, HashSet::from([home.tag]));
+
+        //a claim that got in first leaves nothing to fall back
+        let claimed = task
+            .clone()
+            .apply(&home.org.claim_task(b, &task, now()).unwrap());
+        assert!(home
+            .org
+            .fall_back(&claimed, now(), &mut ChaCha8Rng::seed_from_u64(7))
+            .is_err());
+    }
+
+    #[test]
+    fn trades_go_to_someone_else_in_the_organization() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        let task = home.pending(a);
+        assert!(home.org.propose_trade(a, b, &task, None, now()).is_ok());
+        assert!(matches!(
+            home.org.propose_trade(a, a, &task, None, now()),
+            Err(OrganizationError::InvalidTrade)
+        ));
+        assert!(matches!(
+            home.org
+                .propose_trade(a, AccountId::new(), &task, None, now()),
+            Err(OrganizationError::InvalidTrade)
+        ));
+        //only what the account has itself can be offered
+        assert!(matches!(
+            home.org.propose_trade(b, a, &task, None, now()),
+            Err(OrganizationError::InvalidTrade)
+        ));
+        assert!(matches!(
+            home.org
+                .propose_trade(a, b, &task, Some(&home.pending(a)), now()),
+            Err(OrganizationError::InvalidTrade)
+        ));
+    }
+
+    #[test]
+    fn only_pending_tasks_are_traded() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        let task = home.pending(a);
+        let finished = task.clone().apply(&task.finish(a).unwrap());
+        assert!(matches!(
+            home.org.propose_trade(a, b, &finished, None, now()),
+            Err(OrganizationError::InvalidTrade)
+        ));
+    }
+
+    #[test]
+    fn tag_tasks_stay_within_the_tags() {
+        let home = household(1);
+        let outsider = AccountId::new();
+        let home = home.apply(&OrganizationEvent::AccountLinked {
+            account: outsider,
+            account_type: AccountType::Worker,
+        });
+        let worker = home.workers[0];
+        let task = home.pending(worker).with_tags(HashSet::from([home.tag]));
+        assert!(matches!(
+            home.org.propose_trade(worker, outsider, &task, None, now()),
+            Err(OrganizationError::NotInTag)
+        ));
+        let untagged = home.pending(worker);
+        assert!(home
+            .org
+            .propose_trade(worker, outsider, &untagged, None, now())
+            .is_ok());
+    }
+
+    #[test]
+    fn a_task_is_in_one_trade_at_a_time() {
+        let home = household(3);
+        let [a, b, c] = [home.workers[0], home.workers[1], home.workers[2]];
+        let task = home.pending(a);
+        let proposed = home.org.propose_trade(a, b, &task, None, now()).unwrap();
+        let home = home.apply(&proposed);
+        assert!(matches!(
+            home.org.propose_trade(a, c, &task, None, now()),
+            Err(OrganizationError::TaskAlreadyTraded)
+        ));
+        //the counterparty's side counts too
+        let theirs = home.pending(c);
+        assert!(home
+            .org
+            .propose_trade(c, a, &theirs, Some(&task), now())
+            .is_err());
+    }
+
+    #[test]
+    fn swaps_need_approval_from_whoever_assigned_the_tasks() {
+        let home = household(2);
+        let [a, b] = [home.workers[0], home.workers[1]];
+        let settings = OrganizationSettings {
+            trades_need_approval: true,
+            ..Default::default()
+        };
+        let changed = OrganizationEvent::SettingsChanged {
+            settings,
+            changed_by: home.owner,
+        };
+        let home = home.apply(&changed);
+        let offered = home.pending(a);
+        let requested = home.pending(b);
+        let proposed = home
+            .org
+            .propose_trade(a, b, &offered, Some(&requested), now())
+            .unwrap();
+        let OrganizationEvent::TradeProposed { id, approvers, .. } = &proposed else {
+            panic!("expected a proposal");
+        };
+        assert_eq!(*approvers, BTreeSet::from([home.owner]));
+        let id = *id;
+
+        let home = home.apply(&proposed);
+        assert!(matches!(
+            home.org.accept_trade(a, id),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        let accepted = home.org.accept_trade(b, id).unwrap();
+        let home = home.apply(&accepted);
+        assert!(!home.org.trade_ready(id));
+        assert!(matches!(
+            home.org.complete_trade(id, &offered, Some(&requested)),
+            Err(OrganizationError::InvalidTrade)
+        ));
+        assert!(matches!(
+            home.org.approve_trade(a, id),
+            Err(OrganizationError::NotAuthorized)
+        ));
+        let approved = home.org.approve_trade(home.owner, id).unwrap();
+        let home = home.apply(&approved);
+        assert!(home.org.trade_ready(id));
+
+        //the tasks have to be the ones that were proposed
+        assert!(matches!(
+            home.org.complete_trade(id, &requested, Some(&offered)),
+            Err(OrganizationError::InvalidTrade)
+        ));
+        let (_, events) = home
+            .org
+            .complete_trade(id, &offered, Some(&requested))
+            .unwrap();
+        assert_eq!(offered.clone().apply(&events[0]).assigned_to(), b);
+        assert_eq!(requested.clone().apply(&events[1]).assigned_to(), a);
+    }
+
+    #[test]
+    fn workers_that_fall_short_are_passed_over_and_explained() {
+        let home = household(2);
+        let (grown, child) = (home.workers[0], home.workers[1]);
+        let ironing = CatalogueTaskId::new();
+        let profile = |account, born: &str, skills: &[&str]| {
+            home.org
+                .set_profile(
+                    home.owner,
+                    account,
+                    Some(born.parse().unwrap()),
+                    skills.iter().map(ToString::to_string),
+                )
+                .unwrap()
+        };
+        let org = home
+            .org
+            .clone()
+            .with_catalogue_requirements(
+                ironing,
+                Requirements::new(Some(12), ["ironing".to_string()]),
+            )
+            .apply(&profile(grown, "1990-05-01", &["ironing"]))
+            .apply(&profile(child, "2016-05-01", &[]));
+        let assign = |org: &Organization| {
+            org.assign_tasks_to_tags(
+                &home.owner,
+                &HashSet::from([home.tag]),
+                &[ironing],
+                &TaskAssignmentType::LowestTasks,
+                now(),
+                None,
+                &mut ChaCha8Rng::seed_from_u64(7),
+            )
+        };
+
+        let assigned = assign(&org).unwrap();
+        assert_eq!(assignees(&assigned), vec![grown]);
+        assert_eq!(
+            assigned.ineligible,
+            vec![Ineligibility {
+                account: child,
+                task: ironing,
+                reasons: vec![
+                    IneligibleReason::TooYoung {
+                        min_age: 12,
+                        age: 7
+                    },
+                    IneligibleReason::MissingSkills {
+                        skills: BTreeSet::from(["ironing".to_string()]),
+                    },
+                ],
+            }]
+        );
+
+        //with nobody left to take it the assignment fails and says why
+        let org = org.apply(&profile(grown, "1990-05-01", &[]));
+        match assign(&org) {
+            Err(OrganizationError::Ineligible(ineligible)) => {
+                let mut accounts: Vec<AccountId> = ineligible
+                    .iter()
+                    .map(|ineligibility| ineligibility.account)
+                    .collect();
+                accounts.sort();
+                assert_eq!(accounts, home.workers);
+            }
+            other => panic!("expected Ineligible, got {other:?}"),
+        }
+    }
+
+    impl Household {
+        fn capped(mut self, target: CapTarget, caps: WorkloadCaps) -> Self {
+            self.org = self.org.apply(&OrganizationEvent::WorkloadCapsSet {
+                target,
+                caps,
+                set_by: self.owner,
+            });
+            self
+        }
+    }
+
+    fn one_a_day() -> WorkloadCaps {
+        WorkloadCaps {
+            tasks_per_day: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn capped_workers_spill_over_to_the_next() {
+        let home = household(2);
+        let tag = home.tag;
+        let home = home.capped(CapTarget::Tag(tag), one_a_day());
+        let assigned = home.assign(2, TaskAssignmentType::Random, None).unwrap();
+        let mut assigned = assignees(&assigned);
+        assigned.sort();
+        assert_eq!(assigned, home.workers);
+    }
+
+    #[test]
+    fn fails_when_every_worker_is_capped() {
+        let home = household(2);
+        let tag = home.tag;
+        let home = home.capped(CapTarget::Tag(tag), one_a_day());
+        assert!(matches!(
+            home.assign(3, TaskAssignmentType::LowestTasks, None),
+            Err(OrganizationError::CapReached)
+        ));
+    }
+
+    #[test]
+    fn tasks_count_on_the_day_they_are_due() {
+        let home = household(1);
+        let worker = home.workers[0];
+        let mut workload = Workload::default();
+        workload.days.insert(
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+            DayLoad {
+                tasks: 1,
+                effort: 1,
+            },
+        );
+        let home = Household {
+            org: home.org.clone().with_workload(worker, workload),
+            ..home
+        }
+        .capped(CapTarget::Account(worker), one_a_day());
+
+        assert!(matches!(
+            home.assign(1, TaskAssignmentType::Random, None),
+            Err(OrganizationError::CapReached)
+        ));
+        //due at midnight still counts for the day that ends there
+        let midnight = "2024-01-04T00:00:00Z".parse().unwrap();
+        assert!(matches!(
+            home.assign(1, TaskAssignmentType::Random, Some(midnight)),
+            Err(OrganizationError::CapReached)
+        ));
+        let tomorrow = "2024-01-04T18:00:00Z".parse().unwrap();
+        let assigned = home
+            .assign(1, TaskAssignmentType::Random, Some(tomorrow))
+            .unwrap();
+        assert_eq!(assignees(&assigned), vec![worker]);
+    }
+
+    #[test]
+    fn claims_respect_caps() {
+        let home = household(1);
+        let worker = home.workers[0];
+        let home = home.capped(CapTarget::Account(worker), one_a_day());
+        let task = home.post(None);
+        //already has a task due on the day this one is
+        let mut workload = Workload::default();
+        workload.days.insert(
+            NaiveDate::from_ymd_opt(2024, 1, 4).unwrap(),
+            DayLoad {
+                tasks: 1,
+                effort: 1,
+            },
+        );
+        let home = Household {
+            org: home.org.clone().with_workload(worker, workload),
+            ..home
+        };
+        assert!(matches!(
+            home.org.claim_task(worker, &task, now()),
+            Err(OrganizationError::CapReached)
+        ));
+    }
 }
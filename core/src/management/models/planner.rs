@@ -0,0 +1,374 @@
+use crate::shared::account::AccountId;
+
+use super::workload::{DayLoad, WorkloadCaps};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanWorker {
+    pub account: AccountId,
+    //percent of a full share, see AccountLink::share
+    pub share: u32,
+    pub caps: WorkloadCaps,
+    //tasks already due on each day of the plan and everything already due in its week,
+    //they count towards the caps but not towards the balance
+    pub due: Vec<u32>,
+    pub week: DayLoad,
+}
+
+//one occurrence of a task and the worker and day pairs it may go to
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanItem {
+    pub effort: u32,
+    pub options: Vec<Slot>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub worker: usize,
+    pub day: usize,
+}
+
+//improvement passes stop earlier once no move or swap helps anymore
+const MAX_PASSES: usize = 100;
+
+//scales weighted effort so shares below a full share do not round away
+const SCALE: u64 = 1000;
+
+struct State<'a> {
+    workers: &'a [PlanWorker],
+    items: &'a [PlanItem],
+    slots: Vec<Option<Slot>>,
+    load: Vec<u64>,
+    effort: Vec<u32>,
+    tasks: Vec<u32>,
+    per_day: Vec<Vec<u32>>,
+    day_load: Vec<u64>,
+}
+
+impl<'a> State<'a> {
+    fn new(workers: &'a [PlanWorker], days: usize, items: &'a [PlanItem]) -> Self {
+        Self {
+            workers,
+            items,
+            slots: vec![None; items.len()],
+            load: vec![0; workers.len()],
+            effort: workers.iter().map(|worker| worker.week.effort).collect(),
+            tasks: workers.iter().map(|worker| worker.week.tasks).collect(),
+            per_day: workers
+                .iter()
+                .map(|worker| {
+                    (0..days)
+                        .map(|day| worker.due.get(day).copied().unwrap_or(0))
+                        .collect()
+                })
+                .collect(),
+            day_load: vec![0; days],
+        }
+    }
+
+    fn weighted(&self, worker: usize, effort: u32) -> u64 {
+        u64::from(effort) * SCALE * 100 / u64::from(self.workers[worker].share.max(1))
+    }
+
+    fn fits(&self, item: usize, slot: Slot) -> bool {
+        let caps = &self.workers[slot.worker].caps;
+        let effort = self.effort[slot.worker].saturating_add(self.items[item].effort);
+        caps.tasks_per_day
+            .is_none_or(|cap| self.per_day[slot.worker][slot.day] < cap)
+            && caps
+                .tasks_per_week
+                .is_none_or(|cap| self.tasks[slot.worker] < cap)
+            && caps.effort_per_week.is_none_or(|cap| effort <= cap)
+    }
+
+    fn place(&mut self, item: usize, slot: Slot) {
+        let effort = self.items[item].effort;
+        let weighted = self.weighted(slot.worker, effort);
+        self.load[slot.worker] += weighted;
+        self.effort[slot.worker] = self.effort[slot.worker].saturating_add(effort);
+        self.tasks[slot.worker] += 1;
+        self.per_day[slot.worker][slot.day] += 1;
+        self.day_load[slot.day] += u64::from(effort);
+        self.slots[item] = Some(slot);
+    }
+
+    fn remove(&mut self, item: usize) -> Option<Slot> {
+        let slot = self.slots[item].take()?;
+        let effort = self.items[item].effort;
+        let weighted = self.weighted(slot.worker, effort);
+        self.load[slot.worker] -= weighted;
+        self.effort[slot.worker] = self.effort[slot.worker].saturating_sub(effort);
+        self.tasks[slot.worker] -= 1;
+        self.per_day[slot.worker][slot.day] -= 1;
+        self.day_load[slot.day] -= u64::from(effort);
+        Some(slot)
+    }
+
+    //sums of squares, so lower means closer to even: first between workers, then over each worker's days, then over the household's days
+    //weighted loads are squared times the share, which is lowest when effort follows the shares
+    fn cost(&self) -> (u128, u64, u128) {
+        let squared = |value: u64| u128::from(value) * u128::from(value);
+        (
+            self.load
+                .iter()
+                .zip(self.workers)
+                .map(|(load, worker)| squared(*load) * u128::from(worker.share.max(1)))
+                .sum(),
+            self.per_day
+                .iter()
+                .flatten()
+                .map(|tasks| u64::from(*tasks) * u64::from(*tasks))
+                .sum(),
+            self.day_load.iter().map(|load| squared(*load)).sum(),
+        )
+    }
+
+    fn best_slot(&mut self, item: usize) -> Option<Slot> {
+        let mut best = None;
+        for slot in self.items[item].options.iter().copied() {
+            if !self.fits(item, slot) {
+                continue;
+            }
+            self.place(item, slot);
+            let cost = self.cost();
+            self.remove(item);
+            if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                best = Some((cost, slot));
+            }
+        }
+        best.map(|(_, slot)| slot)
+    }
+
+    //moves one task to a better slot
+    fn improve_move(&mut self, item: usize) -> bool {
+        let cost = self.cost();
+        let Some(current) = self.remove(item) else {
+            return false;
+        };
+        if let Some(slot) = self.best_slot(item) {
+            self.place(item, slot);
+            if self.cost() < cost {
+                return true;
+            }
+            self.remove(item);
+        }
+        self.place(item, current);
+        false
+    }
+
+    //trades the slots of two tasks of different effort
+    fn improve_swap(&mut self, first: usize, second: usize) -> bool {
+        let (Some(a), Some(b)) = (self.slots[first], self.slots[second]) else {
+            return false;
+        };
+        if a.worker == b.worker
+            || self.items[first].effort == self.items[second].effort
+            || !self.items[first].options.contains(&b)
+            || !self.items[second].options.contains(&a)
+        {
+            return false;
+        }
+        let cost = self.cost();
+        self.remove(first);
+        self.remove(second);
+        if self.fits(first, b) {
+            self.place(first, b);
+            if self.fits(second, a) {
+                self.place(second, a);
+                if self.cost() < cost {
+                    return true;
+                }
+                self.remove(second);
+            }
+            self.remove(first);
+        }
+        self.place(first, a);
+        self.place(second, b);
+        false
+    }
+}
+
+//a slot for every item, None when the greedy start already runs out of room for one of them
+pub fn plan(workers: &[PlanWorker], days: usize, items: &[PlanItem]) -> Option<Vec<Slot>> {
+    let mut state = State::new(workers, days, items);
+
+    //the biggest and most constrained tasks go first while there is still room
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|item| {
+        (
+            std::cmp::Reverse(items[*item].effort),
+            items[*item].options.len(),
+        )
+    });
+    for item in order {
+        let slot = state.best_slot(item)?;
+        state.place(item, slot);
+    }
+
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for item in 0..items.len() {
+            improved |= state.improve_move(item);
+        }
+        for first in 0..items.len() {
+            for second in first + 1..items.len() {
+                improved |= state.improve_swap(first, second);
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    state.slots.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(share: u32, caps: WorkloadCaps) -> PlanWorker {
+        PlanWorker {
+            account: AccountId::new(),
+            share,
+            caps,
+            due: Vec::new(),
+            week: DayLoad::default(),
+        }
+    }
+
+    //every worker on every day
+    fn anywhere(effort: u32, workers: usize, days: usize) -> PlanItem {
+        PlanItem {
+            effort,
+            options: (0..workers)
+                .flat_map(|worker| (0..days).map(move |day| Slot { worker, day }))
+                .collect(),
+        }
+    }
+
+    fn effort_of(worker: usize, items: &[PlanItem], slots: &[Slot]) -> u32 {
+        items
+            .iter()
+            .zip(slots)
+            .filter(|(_, slot)| slot.worker == worker)
+            .map(|(item, _)| item.effort)
+            .sum()
+    }
+
+    #[test]
+    fn balances_effort_between_workers() {
+        let workers = vec![worker(100, WorkloadCaps::default()); 2];
+        let items: Vec<PlanItem> = [5, 3, 2, 4, 1, 1]
+            .into_iter()
+            .map(|effort| anywhere(effort, 2, 7))
+            .collect();
+        let slots = plan(&workers, 7, &items).unwrap();
+        assert_eq!(effort_of(0, &items, &slots), 8);
+        assert_eq!(effort_of(1, &items, &slots), 8);
+    }
+
+    #[test]
+    fn weighs_effort_by_share() {
+        let workers = vec![
+            worker(100, WorkloadCaps::default()),
+            worker(50, WorkloadCaps::default()),
+        ];
+        let items: Vec<PlanItem> = (0..6).map(|_| anywhere(1, 2, 7)).collect();
+        let slots = plan(&workers, 7, &items).unwrap();
+        assert_eq!(effort_of(0, &items, &slots), 4);
+        assert_eq!(effort_of(1, &items, &slots), 2);
+    }
+
+    #[test]
+    fn spreads_tasks_over_the_days() {
+        let workers = vec![worker(100, WorkloadCaps::default())];
+        let items: Vec<PlanItem> = (0..7).map(|_| anywhere(1, 1, 7)).collect();
+        let slots = plan(&workers, 7, &items).unwrap();
+        let mut days: Vec<usize> = slots.iter().map(|slot| slot.day).collect();
+        days.sort();
+        assert_eq!(days, (0..7).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn only_uses_the_options() {
+        let workers = vec![worker(100, WorkloadCaps::default()); 2];
+        let only = Slot { worker: 1, day: 3 };
+        let items = vec![
+            PlanItem {
+                effort: 5,
+                options: vec![only],
+            },
+            anywhere(1, 2, 7),
+        ];
+        let slots = plan(&workers, 7, &items).unwrap();
+        assert_eq!(slots[0], only);
+    }
+
+    #[test]
+    fn fails_without_options() {
+        let workers = vec![worker(100, WorkloadCaps::default())];
+        let items = vec![PlanItem {
+            effort: 1,
+            options: Vec::new(),
+        }];
+        assert_eq!(plan(&workers, 7, &items), None);
+        assert_eq!(plan(&[], 7, &[anywhere(1, 0, 7)]), None);
+    }
+
+    #[test]
+    fn nothing_to_plan() {
+        assert_eq!(plan(&[], 7, &[]), Some(Vec::new()));
+    }
+
+    #[test]
+    fn respects_the_caps() {
+        let caps = WorkloadCaps {
+            tasks_per_day: Some(1),
+            tasks_per_week: Some(3),
+            ..Default::default()
+        };
+        let workers = vec![worker(100, caps)];
+
+        let slots = plan(&workers, 7, &[anywhere(1, 1, 2), anywhere(1, 1, 2)]).unwrap();
+        assert_ne!(slots[0].day, slots[1].day);
+        //one a day leaves no room for a third task on two days
+        assert_eq!(plan(&workers, 2, &vec![anywhere(1, 1, 2); 3]), None);
+        assert_eq!(plan(&workers, 7, &vec![anywhere(1, 1, 7); 4]), None);
+    }
+
+    #[test]
+    fn fails_when_every_worker_is_capped() {
+        let caps = WorkloadCaps {
+            effort_per_week: Some(3),
+            ..Default::default()
+        };
+        let workers = vec![worker(100, caps); 2];
+        let items: Vec<PlanItem> = (0..3).map(|_| anywhere(2, 2, 7)).collect();
+        assert_eq!(plan(&workers, 7, &items), None);
+        assert!(plan(&workers, 7, &items[..2]).is_some());
+    }
+
+    #[test]
+    fn caps_count_what_is_already_due() {
+        let caps = WorkloadCaps {
+            tasks_per_day: Some(1),
+            tasks_per_week: Some(3),
+            ..Default::default()
+        };
+        let mut busy = worker(100, caps);
+        busy.due = vec![1, 1, 0, 0, 0, 0, 0];
+        busy.week = DayLoad {
+            tasks: 2,
+            effort: 2,
+        };
+        let workers = vec![busy];
+
+        let slots = plan(&workers, 7, &[anywhere(1, 1, 7)]).unwrap();
+        assert!(slots[0].day >= 2);
+        //the week has room for one more only
+        assert_eq!(
+            plan(&workers, 7, &[anywhere(1, 1, 7), anywhere(1, 1, 7)]),
+            None
+        );
+    }
+}
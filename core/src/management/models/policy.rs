@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::organization::{AccountType, TagId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Capability {
+    #[serde(rename = "catalogue.write")]
+    CatalogueWrite,
+    #[serde(rename = "tag.create")]
+    TagCreate,
+    #[serde(rename = "tag.manage")]
+    TagManage,
+    #[serde(rename = "task.assign")]
+    TaskAssign,
+    #[serde(rename = "task.add_time")]
+    TaskAddTime,
+    #[serde(rename = "account.manage")]
+    AccountManage,
+    #[serde(rename = "organization.configure")]
+    OrganizationConfigure,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 7] = [
+        Capability::CatalogueWrite,
+        Capability::TagCreate,
+        Capability::TagManage,
+        Capability::TaskAssign,
+        Capability::TaskAddTime,
+        Capability::AccountManage,
+        Capability::OrganizationConfigure,
+    ];
+
+    //editors of a tag hold these for everything that only touches their tags
+    pub const EDITOR: [Capability; 3] = [
+        Capability::TagManage,
+        Capability::TaskAssign,
+        Capability::TaskAddTime,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::CatalogueWrite => "catalogue.write",
+            Capability::TagCreate => "tag.create",
+            Capability::TagManage => "tag.manage",
+            Capability::TaskAssign => "task.assign",
+            Capability::TaskAddTime => "task.add_time",
+            Capability::AccountManage => "account.manage",
+            Capability::OrganizationConfigure => "organization.configure",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Capability> {
+        Capability::ALL
+            .into_iter()
+            .find(|capability| capability.as_str() == value)
+    }
+}
+
+//what an action touches, tag editorships only count for actions scoped to tags
+#[derive(Debug, Clone, Copy)]
+pub enum Scope<'a> {
+    Organization,
+    Tags(&'a HashSet<TagId>),
+}
+
+//role to capability grants, the owner always holds every capability so an organization cannot lock itself out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    grants: HashMap<AccountType, HashSet<Capability>>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            grants: HashMap::from([
+                (AccountType::Worker, HashSet::from([Capability::TagCreate])),
+                (
+                    AccountType::Admin,
+                    HashSet::from([
+                        Capability::CatalogueWrite,
+                        Capability::TagCreate,
+                        Capability::TaskAssign,
+                        Capability::TaskAddTime,
+                        Capability::AccountManage,
+                        Capability::OrganizationConfigure,
+                    ]),
+                ),
+            ]),
+        }
+    }
+}
+
+impl Policy {
+    pub fn allows(&self, account_type: AccountType, capability: Capability) -> bool {
+        account_type == AccountType::Owner
+            || self
+                .grants
+                .get(&account_type)
+                .is_some_and(|grants| grants.contains(&capability))
+    }
+
+    pub fn grants(&self, account_type: AccountType) -> HashSet<Capability> {
+        match account_type {
+            AccountType::Owner => HashSet::from(Capability::ALL),
+            _ => self.grants.get(&account_type).cloned().unwrap_or_default(),
+        }
+    }
+
+    pub fn set(&mut self, account_type: AccountType, capabilities: HashSet<Capability>) {
+        if account_type != AccountType::Owner {
+            self.grants.insert(account_type, capabilities);
+        }
+    }
+}
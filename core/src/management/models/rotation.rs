@@ -0,0 +1,129 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{catalogue::CatalogueTaskId, shared::account::AccountId};
+
+use super::organization::TagId;
+
+//what a round robin rotates over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum Rotation {
+    //one rotation for every set of tags tasks are assigned to
+    #[default]
+    Tags,
+    //one rotation for every catalogue task, whatever the tags
+    Task,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum RotationKey {
+    Tags(BTreeSet<TagId>),
+    Task(CatalogueTaskId),
+}
+
+//the last account each rotation handed a task to
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Rotations {
+    last: HashMap<RotationKey, AccountId>,
+}
+
+impl Rotations {
+    //the worker after the last one in account order, so joining workers slot in and leaving ones drop out
+    //without anyone getting a second turn
+    pub fn next(&mut self, key: RotationKey, workers: &[AccountId]) -> Option<AccountId> {
+        let mut workers = workers.to_vec();
+        workers.sort();
+        let next = match self.last.get(&key) {
+            Some(last) => workers
+                .iter()
+                .find(|&worker| worker > last)
+                .or(workers.first()),
+            None => workers.first(),
+        }
+        .copied()?;
+        self.last.insert(key, next);
+        Some(next)
+    }
+
+    pub fn set(&mut self, key: RotationKey, last: AccountId) {
+        self.last.insert(key, last);
+    }
+
+    //cursors that moved compared to an earlier state
+    pub fn changes_since(self, before: &Rotations) -> Vec<(RotationKey, AccountId)> {
+        self.last
+            .into_iter()
+            .filter(|(key, last)| before.last.get(key) != Some(last))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accounts(count: usize) -> Vec<AccountId> {
+        let mut accounts: Vec<AccountId> = (0..count).map(|_| AccountId::new()).collect();
+        accounts.sort();
+        accounts
+    }
+
+    fn key() -> RotationKey {
+        RotationKey::Task(CatalogueTaskId::from(ulid::Ulid::nil()))
+    }
+
+    #[test]
+    fn takes_turns_in_account_order() {
+        let workers = accounts(3);
+        let mut shuffled = workers.clone();
+        shuffled.reverse();
+        let mut rotations = Rotations::default();
+        let turns: Vec<AccountId> = (0..4)
+            .filter_map(|_| rotations.next(key(), &shuffled))
+            .collect();
+        assert_eq!(turns, vec![workers[0], workers[1], workers[2], workers[0]]);
+    }
+
+    #[test]
+    fn nobody_to_rotate_over() {
+        let mut rotations = Rotations::default();
+        assert_eq!(rotations.next(key(), &[]), None);
+        assert!(rotations.changes_since(&Rotations::default()).is_empty());
+    }
+
+    #[test]
+    fn continues_after_the_last_one_leaves() {
+        let workers = accounts(3);
+        let mut rotations = Rotations::default();
+        rotations.set(key(), workers[1]);
+        let remaining = [workers[0], workers[2]];
+        //the next one after whoever left still gets the turn
+        assert_eq!(rotations.next(key(), &remaining), Some(workers[2]));
+
+        rotations.set(key(), workers[2]);
+        assert_eq!(rotations.next(key(), &workers[..2]), Some(workers[0]));
+    }
+
+    #[test]
+    fn joining_workers_slot_in() {
+        let workers = accounts(3);
+        let mut rotations = Rotations::default();
+        rotations.set(key(), workers[0]);
+        assert_eq!(rotations.next(key(), &workers), Some(workers[1]));
+    }
+
+    #[test]
+    fn rotations_are_separate() {
+        let workers = accounts(2);
+        let tags = RotationKey::Tags(BTreeSet::new());
+        let mut rotations = Rotations::default();
+        let before = rotations.clone();
+        assert_eq!(rotations.next(key(), &workers), Some(workers[0]));
+        assert_eq!(rotations.next(tags.clone(), &workers), Some(workers[0]));
+        assert_eq!(rotations.next(key(), &workers), Some(workers[1]));
+        let mut changes = rotations.changes_since(&before);
+        changes.sort_by_key(|(_, last)| *last);
+        assert_eq!(changes, vec![(tags, workers[0]), (key(), workers[1])]);
+    }
+}
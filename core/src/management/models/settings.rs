@@ -0,0 +1,146 @@
+use chrono::{
+    DateTime, Datelike, Days, Duration, Locale, NaiveDate, NaiveTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use super::organization::{OrganizationError, TaskAssignmentType};
+
+//when tasks expire if an assignment does not say
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum DefaultExpiry {
+    After(#[serde(with = "crate::shared::serde_duration::seconds")] Duration),
+    //local midnight at the end of the day the task was assigned
+    EndOfDay,
+    //local midnight before the next week starts
+    EndOfWeek,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OrganizationSettings {
+    pub time_zone: Tz,
+    pub week_start: Weekday,
+    pub default_expiry: Option<DefaultExpiry>,
+    pub default_assignment_type: TaskAssignmentType,
+    //posix style name such as en_US or de_DE, used to format dates for members
+    pub locale: String,
+    //how many days of assigned effort the lowest effort strategy looks back on
+    #[serde(default = "default_effort_window_days")]
+    pub effort_window_days: u32,
+    //whether whoever assigned a traded task has to approve the trade
+    #[serde(default)]
+    pub trades_need_approval: bool,
+}
+
+fn default_effort_window_days() -> u32 {
+    7
+}
+
+impl Default for OrganizationSettings {
+    fn default() -> Self {
+        Self {
+            time_zone: Tz::UTC,
+            week_start: Weekday::Mon,
+            default_expiry: None,
+            default_assignment_type: TaskAssignmentType::Random,
+            locale: "en_US".to_string(),
+            effort_window_days: default_effort_window_days(),
+            trades_need_approval: false,
+        }
+    }
+}
+
+impl OrganizationSettings {
+    pub fn validate(&self) -> Result<(), OrganizationError> {
+        if Locale::try_from(self.locale.as_str()).is_err() {
+            return Err(OrganizationError::UnknownLocale);
+        }
+        if self.effort_window_days == 0 {
+            return Err(OrganizationError::InvalidEffortWindow);
+        }
+        match self.default_expiry {
+            Some(DefaultExpiry::After(duration)) if duration <= Duration::zero() => {
+                Err(OrganizationError::InvalidDefaultExpiry)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn locale(&self) -> Locale {
+        Locale::try_from(self.locale.as_str()).unwrap_or(Locale::POSIX)
+    }
+
+    pub fn local_date(&self, at: DateTime<Utc>) -> NaiveDate {
+        at.with_timezone(&self.time_zone).date_naive()
+    }
+
+    pub fn start_of_day(&self, date: NaiveDate) -> DateTime<Utc> {
+        self.local_time(date, NaiveTime::MIN)
+    }
+
+    //a time can fall into a daylight saving gap, it then moves to the first valid instant after it
+    pub fn local_time(&self, date: NaiveDate, time: NaiveTime) -> DateTime<Utc> {
+        let local = date.and_time(time);
+        (0..=3)
+            .find_map(|hours| {
+                self.time_zone
+                    .from_local_datetime(&(local + Duration::hours(hours)))
+                    .earliest()
+            })
+            .map(|start| start.with_timezone(&Utc))
+            .unwrap_or(local.and_utc())
+    }
+
+    pub fn end_of_day(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let today = self.local_date(at);
+        self.start_of_day(today.succ_opt().unwrap_or(today))
+    }
+
+    pub fn week_start_of(&self, at: DateTime<Utc>) -> NaiveDate {
+        self.first_day_of_week(self.local_date(at))
+    }
+
+    pub fn first_day_of_week(&self, date: NaiveDate) -> NaiveDate {
+        let since_start = (7 + date.weekday().num_days_from_monday()
+            - self.week_start.num_days_from_monday())
+            % 7;
+        date - Days::new(since_start.into())
+    }
+
+    //the day a deadline falls on, one at midnight closes the day before
+    pub fn due_date(&self, due: DateTime<Utc>) -> NaiveDate {
+        self.local_date(due - Duration::nanoseconds(1))
+    }
+
+    pub fn end_of_week(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let start = self.week_start_of(at);
+        self.start_of_day(start.checked_add_days(Days::new(7)).unwrap_or(start))
+    }
+
+    pub fn effort_window_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.effort_window_days.into())
+    }
+
+    pub fn default_expires(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.default_expiry.map(|expiry| match expiry {
+            DefaultExpiry::After(duration) => now + duration,
+            DefaultExpiry::EndOfDay => self.end_of_day(now),
+            DefaultExpiry::EndOfWeek => self.end_of_week(now),
+        })
+    }
+
+    //days are counted on the local calendar so a repeat keeps its wall clock time across daylight saving changes
+    pub fn add_days(&self, at: DateTime<Utc>, days: Days) -> Option<DateTime<Utc>> {
+        at.with_timezone(&self.time_zone)
+            .checked_add_days(days)
+            .map(|local| local.with_timezone(&Utc))
+            .or_else(|| at.checked_add_days(days))
+    }
+
+    pub fn sub_days(&self, at: DateTime<Utc>, days: Days) -> Option<DateTime<Utc>> {
+        at.with_timezone(&self.time_zone)
+            .checked_sub_days(days)
+            .map(|local| local.with_timezone(&Utc))
+            .or_else(|| at.checked_sub_days(days))
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc, Weekday};
+use serde::Serialize;
+
+use crate::shared::account::AccountId;
+
+use super::{events::TaskEvent, organization::OrganizationId, task::TaskInstance};
+
+//every event of one task, oldest first
+pub type TaskStream = Vec<(DateTime<Utc>, TaskEvent)>;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyStats {
+    pub organization: OrganizationId,
+    pub account: AccountId,
+    //monday of the iso week, weeks run monday to monday in utc
+    pub week_start: NaiveDate,
+    pub assigned: u32,
+    pub finished: u32,
+    pub rejected: u32,
+    pub expired: u32,
+}
+
+impl WeeklyStats {
+    fn new(organization: OrganizationId, account: AccountId, week_start: NaiveDate) -> WeeklyStats {
+        WeeklyStats {
+            organization,
+            account,
+            week_start,
+            assigned: 0,
+            finished: 0,
+            rejected: 0,
+            expired: 0,
+        }
+    }
+
+    pub fn iso_year(&self) -> i32 {
+        self.week_start.iso_week().year()
+    }
+
+    pub fn iso_week(&self) -> u32 {
+        self.week_start.iso_week().week()
+    }
+}
+
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date.week(Weekday::Mon).first_day()
+}
+
+pub fn week_bounds(week_start: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = week_start.and_time(Default::default()).and_utc();
+    (start, start + Days::new(7))
+}
+
+//counts the events of one week, streams must hold every event of a task up to the end of the week
+pub fn weekly_stats(week_start: NaiveDate, streams: &[TaskStream]) -> Vec<WeeklyStats> {
+    let (start, end) = week_bounds(week_start);
+    let mut stats: BTreeMap<(OrganizationId, AccountId), WeeklyStats> = BTreeMap::new();
+
+    for stream in streams {
+        let mut task = TaskInstance::default();
+        for (created, event) in stream {
+            task = task.apply(event);
+            if *created < start || *created >= end {
+                continue;
+            }
+
+            let counter: fn(&mut WeeklyStats) -> &mut u32 = match event {
+                TaskEvent::Assigned { .. } | TaskEvent::Claimed { .. } => {
+                    |stats| &mut stats.assigned
+                }
+                TaskEvent::Finished { .. } => |stats| &mut stats.finished,
+                TaskEvent::Rejected { .. } => |stats| &mut stats.rejected,
+                TaskEvent::Expired { .. } => |stats| &mut stats.expired,
+                TaskEvent::Posted { .. }
+                | TaskEvent::Unclaimed { .. }
+                | TaskEvent::ClaimDeadlinePassed { .. }
+                | TaskEvent::HandedOver { .. }
+                | TaskEvent::TimeAdded { .. }
+                | TaskEvent::Cancelled { .. } => continue,
+            };
+            let entry = stats
+                .entry((task.organization(), task.assigned_to()))
+                .or_insert_with(|| {
+                    WeeklyStats::new(task.organization(), task.assigned_to(), week_start)
+                });
+            *counter(entry) += 1;
+        }
+    }
+
+    stats.into_values().collect()
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use chrono::{DateTime, Duration, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::types::Uuid;
@@ -6,7 +8,11 @@ use ulid::Ulid;
 
 use crate::{catalogue::CatalogueTaskId, shared::account::AccountId};
 
-use super::events::TaskEvent;
+use super::{
+    events::TaskEvent,
+    organization::{OrganizationId, TagId, TaskAssignmentType},
+    trade::TradeId,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, Deserialize)]
 pub struct TaskId(pub Ulid);
@@ -27,19 +33,37 @@ impl From<Uuid> for TaskId {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct TaskInstance {
     id: TaskId,
+    organization: OrganizationId,
     catalogue_id: CatalogueTaskId,
     assigned_to: AccountId,
     assigned_by: AccountId,
     expires: Option<DateTime<Utc>>,
     status: TaskStatus,
+    tags: HashSet<TagId>,
+    reassigned_from: Option<TaskId>,
+    reassignments: u32,
+    //seed and sorted candidates of the random pick that chose assigned_to
+    seed: Option<u64>,
+    candidates: Vec<AccountId>,
+    //posted to the pool for workers of its tags to claim, open until someone does
+    pooled: bool,
+    claim_deadline: Option<ClaimDeadline>,
+}
+
+//when nobody claimed a pooled task in time it is assigned the usual way
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ClaimDeadline {
+    pub at: DateTime<Utc>,
+    pub fallback: TaskAssignmentType,
 }
 
 impl TaskInstance {
     pub fn new(
         id: TaskId,
+        organization: OrganizationId,
         assigned_to: AccountId,
         assigned_by: AccountId,
         expires: Option<DateTime<Utc>>,
@@ -48,21 +72,219 @@ impl TaskInstance {
     ) -> Result<TaskInstance, TaskDomainError> {
         Ok(TaskInstance {
             id,
+            organization,
             assigned_to,
             assigned_by,
             expires,
             catalogue_id: task,
             status,
+            tags: HashSet::new(),
+            reassigned_from: None,
+            reassignments: 0,
+            seed: None,
+            candidates: Vec::new(),
+            pooled: false,
+            claim_deadline: None,
         })
     }
 
+    //tags the task was assigned through, reassignment picks the next worker from them
+    pub fn with_tags(mut self, tags: HashSet<TagId>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    //lets a previewed assignment go to someone else before it is confirmed, it is no longer a random pick
+    pub fn with_assigned_to(mut self, assigned_to: AccountId) -> Self {
+        self.assigned_to = assigned_to;
+        self.seed = None;
+        self.candidates = Vec::new();
+        self
+    }
+
+    //records what organization::random_pick needs to pick assigned_to again
+    pub fn with_random_pick(mut self, seed: u64, mut candidates: Vec<AccountId>) -> Self {
+        candidates.sort();
+        self.seed = Some(seed);
+        self.candidates = candidates;
+        self
+    }
+
+    pub fn with_pool(mut self, pooled: bool, claim_deadline: Option<ClaimDeadline>) -> Self {
+        self.pooled = pooled;
+        self.claim_deadline = claim_deadline;
+        self
+    }
+
+    pub fn with_reassignment(
+        mut self,
+        reassigned_from: Option<TaskId>,
+        reassignments: u32,
+    ) -> Self {
+        self.reassigned_from = reassigned_from;
+        self.reassignments = reassignments;
+        self
+    }
+
+    pub fn as_reassignment_of(self, previous: &TaskInstance) -> Self {
+        self.with_tags(previous.tags.clone())
+            .with_reassignment(Some(previous.id), previous.reassignments + 1)
+    }
+
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    pub fn organization(&self) -> OrganizationId {
+        self.organization
+    }
+
+    pub fn catalogue_id(&self) -> CatalogueTaskId {
+        self.catalogue_id
+    }
+
+    pub fn assigned_to(&self) -> AccountId {
+        self.assigned_to
+    }
+
+    pub fn assigned_by(&self) -> AccountId {
+        self.assigned_by
+    }
+
+    pub fn expires(&self) -> Option<DateTime<Utc>> {
+        self.expires
+    }
+
+    pub fn status(&self) -> &TaskStatus {
+        &self.status
+    }
+
+    pub fn tags(&self) -> &HashSet<TagId> {
+        &self.tags
+    }
+
+    pub fn reassigned_from(&self) -> Option<TaskId> {
+        self.reassigned_from
+    }
+
+    pub fn reassignments(&self) -> u32 {
+        self.reassignments
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn candidates(&self) -> &[AccountId] {
+        &self.candidates
+    }
+
+    pub fn pooled(&self) -> bool {
+        self.pooled
+    }
+
+    pub fn claim_deadline(&self) -> Option<&ClaimDeadline> {
+        self.claim_deadline.as_ref()
+    }
+
+    //open tasks belong to whoever posted them until they are claimed
+    pub fn post(&self) -> TaskEvent {
+        TaskEvent::Posted {
+            id: self.id,
+            organization: self.organization,
+            posted_by: self.assigned_by,
+            task: self.catalogue_id,
+            expires: self.expires,
+            tags: self.tags.clone(),
+            claim_deadline: self.claim_deadline.clone(),
+        }
+    }
+
+    //the organization decides who may claim, see Organization::claim_task
+    pub fn claim(&self, account: AccountId) -> Result<TaskEvent, TaskDomainError> {
+        match self.status {
+            TaskStatus::Open => Ok(TaskEvent::Claimed {
+                task_id: self.id,
+                account,
+            }),
+            TaskStatus::Pending if self.pooled => Err(TaskDomainError::AlreadyClaimed),
+            _ => Err(TaskDomainError::StatusNotApplicable),
+        }
+    }
+
+    //puts a claimed task back into the pool
+    pub fn unclaim(&self, account: AccountId) -> Result<TaskEvent, TaskDomainError> {
+        if !self.pooled {
+            return Err(TaskDomainError::StatusNotApplicable);
+        }
+        if account != self.assigned_to {
+            return Err(TaskDomainError::NotAuthorized);
+        }
+        match self.status {
+            TaskStatus::Pending => Ok(TaskEvent::Unclaimed {
+                task_id: self.id,
+                account,
+            }),
+            _ => Err(TaskDomainError::StatusNotApplicable),
+        }
+    }
+
+    //the organization decides whether the trade may go ahead, see Organization::complete_trade
+    pub fn hand_over(
+        &self,
+        from: AccountId,
+        to: AccountId,
+        trade: TradeId,
+    ) -> Result<TaskEvent, TaskDomainError> {
+        if from != self.assigned_to {
+            return Err(TaskDomainError::NotAuthorized);
+        }
+        match self.status {
+            TaskStatus::Pending => Ok(TaskEvent::HandedOver {
+                task_id: self.id,
+                from,
+                to,
+                trade,
+            }),
+            _ => Err(TaskDomainError::StatusNotApplicable),
+        }
+    }
+
+    pub fn pass_claim_deadline(&self) -> Result<TaskEvent, TaskDomainError> {
+        match (&self.status, &self.claim_deadline) {
+            (TaskStatus::Open, Some(_)) => Ok(TaskEvent::ClaimDeadlinePassed { task_id: self.id }),
+            _ => Err(TaskDomainError::StatusNotApplicable),
+        }
+    }
+
+    //claims and trades race each other, repositories check them again against the locked current state
+    pub fn verify(&self, event: &TaskEvent) -> Result<(), TaskDomainError> {
+        match event {
+            TaskEvent::Claimed { account, .. } => self.claim(*account).map(drop),
+            TaskEvent::Unclaimed { account, .. } => self.unclaim(*account).map(drop),
+            TaskEvent::ClaimDeadlinePassed { .. } => self.pass_claim_deadline().map(drop),
+            TaskEvent::HandedOver {
+                from, to, trade, ..
+            } => self.hand_over(*from, *to, *trade).map(drop),
+            //the task may have been finished after it was picked up for expiry
+            TaskEvent::Expired { .. } => self.expire().map(drop),
+            _ => Ok(()),
+        }
+    }
+
     pub fn create(&self) -> TaskEvent {
         TaskEvent::Assigned {
             id: self.id,
+            organization: self.organization,
             assigned_to: self.assigned_to,
             assigned_by: self.assigned_by,
             task: self.catalogue_id,
             expires: self.expires,
+            tags: self.tags.clone(),
+            reassigned_from: self.reassigned_from,
+            reassignments: self.reassignments,
+            seed: self.seed,
+            candidates: self.candidates.clone(),
         }
     }
 
@@ -72,9 +294,11 @@ impl TaskInstance {
         }
         match self.status {
             TaskStatus::Pending => Ok(TaskEvent::Finished { task_id: self.id }),
-            TaskStatus::Finished | TaskStatus::Rejected | TaskStatus::Expired => {
-                Err(TaskDomainError::StatusNotApplicable)
-            }
+            TaskStatus::Open
+            | TaskStatus::Finished
+            | TaskStatus::Rejected
+            | TaskStatus::Expired
+            | TaskStatus::Cancelled => Err(TaskDomainError::StatusNotApplicable),
         }
     }
 
@@ -87,34 +311,65 @@ impl TaskInstance {
                 task_id: self.id,
                 assigned_by: self.assigned_by,
             }),
-            TaskStatus::Finished | TaskStatus::Rejected | TaskStatus::Expired => {
-                Err(TaskDomainError::StatusNotApplicable)
-            }
+            TaskStatus::Open
+            | TaskStatus::Finished
+            | TaskStatus::Rejected
+            | TaskStatus::Expired
+            | TaskStatus::Cancelled => Err(TaskDomainError::StatusNotApplicable),
         }
     }
 
     pub fn expire(&self) -> Result<TaskEvent, TaskDomainError> {
         match self.status {
-            TaskStatus::Pending => Ok(TaskEvent::Expired {
+            //an open task nobody claimed expires the same way
+            TaskStatus::Pending | TaskStatus::Open => Ok(TaskEvent::Expired {
                 task_id: self.id,
                 assigned_by: self.assigned_by,
             }),
-            TaskStatus::Finished | TaskStatus::Rejected | TaskStatus::Expired => {
-                Err(TaskDomainError::StatusNotApplicable)
-            }
+            TaskStatus::Finished
+            | TaskStatus::Rejected
+            | TaskStatus::Expired
+            | TaskStatus::Cancelled => Err(TaskDomainError::StatusNotApplicable),
         }
     }
 
-    pub fn add_time(
+    //the organization decides who may cancel, see Organization::release_tasks
+    pub fn cancel(&self, requesting_account: AccountId) -> Result<TaskEvent, TaskDomainError> {
+        match self.status {
+            TaskStatus::Pending => Ok(TaskEvent::Cancelled {
+                task_id: self.id,
+                cancelled_by: requesting_account,
+            }),
+            TaskStatus::Open
+            | TaskStatus::Finished
+            | TaskStatus::Rejected
+            | TaskStatus::Expired
+            | TaskStatus::Cancelled => Err(TaskDomainError::StatusNotApplicable),
+        }
+    }
+
+    //thresholds the remaining time has dropped below, tightest first, expects sorted thresholds
+    pub fn crossed_reminder_thresholds(
         &self,
-        requesting_account: AccountId,
-        time: Duration,
-    ) -> Result<TaskEvent, TaskDomainError> {
-        if requesting_account != self.assigned_by {
-            return Err(TaskDomainError::NotAuthorized);
+        now: DateTime<Utc>,
+        thresholds: &[Duration],
+    ) -> Vec<Duration> {
+        match (&self.status, self.expires) {
+            (TaskStatus::Pending, Some(expires)) if expires > now => thresholds
+                .iter()
+                .filter(|&&threshold| expires - now <= threshold)
+                .copied()
+                .collect(),
+            _ => Vec::new(),
         }
+    }
+
+    //the organization decides who may add time, see Organization::add_time_to_task
+    pub fn add_time(&self, time: Duration) -> Result<TaskEvent, TaskDomainError> {
         match self.status {
-            TaskStatus::Expired => Err(TaskDomainError::StatusNotApplicable),
+            TaskStatus::Expired | TaskStatus::Cancelled => {
+                Err(TaskDomainError::StatusNotApplicable)
+            }
             _ => match self.expires {
                 Some(_) => Ok(TaskEvent::TimeAdded {
                     task_id: self.id,
@@ -129,24 +384,78 @@ impl TaskInstance {
         match event {
             TaskEvent::Assigned {
                 id,
+                organization,
                 assigned_to,
                 assigned_by,
                 task,
                 expires,
+                tags,
+                reassigned_from,
+                reassignments,
+                seed,
+                candidates,
+            } => {
+                self.id = *id;
+                self.organization = *organization;
+                self.assigned_to = *assigned_to;
+                self.assigned_by = *assigned_by;
+                self.catalogue_id = *task;
+                self.expires = *expires;
+                self.tags = tags.clone();
+                self.reassigned_from = *reassigned_from;
+                self.reassignments = *reassignments;
+                self.seed = *seed;
+                self.candidates = candidates.clone();
+            }
+            TaskEvent::Posted {
+                id,
+                organization,
+                posted_by,
+                task,
+                expires,
+                tags,
+                claim_deadline,
             } => {
-                self.id = id.clone();
-                self.assigned_to = assigned_to.clone();
-                self.assigned_by = assigned_by.clone();
-                self.catalogue_id = task.clone();
+                self.id = *id;
+                self.organization = *organization;
+                self.assigned_to = *posted_by;
+                self.assigned_by = *posted_by;
+                self.catalogue_id = *task;
                 self.expires = *expires;
+                self.tags = tags.clone();
+                self.status = TaskStatus::Open;
+                self.pooled = true;
+                self.claim_deadline = claim_deadline.clone();
             }
+            TaskEvent::Claimed {
+                task_id: _,
+                account,
+            } => {
+                self.assigned_to = *account;
+                self.status = TaskStatus::Pending;
+            }
+            TaskEvent::Unclaimed {
+                task_id: _,
+                account: _,
+            } => {
+                self.assigned_to = self.assigned_by;
+                self.status = TaskStatus::Open;
+            }
+            TaskEvent::HandedOver {
+                task_id: _,
+                from: _,
+                to,
+                trade: _,
+            } => self.assigned_to = *to,
+            //the fallback assignment creates new tasks, the open one is done with
+            TaskEvent::ClaimDeadlinePassed { task_id: _ } => self.status = TaskStatus::Cancelled,
             TaskEvent::Finished { task_id: _ } => self.status = TaskStatus::Finished,
             TaskEvent::TimeAdded {
                 task_id: _,
                 duration,
             } => {
-                if let Some(mut expiration_time) = self.expires {
-                    expiration_time += *duration;
+                if let Some(expiration_time) = self.expires.as_mut() {
+                    *expiration_time += *duration;
                 }
             }
             TaskEvent::Rejected {
@@ -157,6 +466,10 @@ impl TaskInstance {
                 task_id: _,
                 assigned_by: _,
             } => self.status = TaskStatus::Expired,
+            TaskEvent::Cancelled {
+                task_id: _,
+                cancelled_by: _,
+            } => self.status = TaskStatus::Cancelled,
         };
 
         self
@@ -167,9 +480,37 @@ impl TaskInstance {
 pub enum TaskStatus {
     #[default]
     Pending,
+    //in the pool, waiting to be claimed
+    Open,
     Finished,
     Rejected,
     Expired,
+    Cancelled,
+}
+
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "pending",
+            TaskStatus::Open => "open",
+            TaskStatus::Finished => "finished",
+            TaskStatus::Rejected => "rejected",
+            TaskStatus::Expired => "expired",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<TaskStatus> {
+        match value {
+            "pending" => Some(TaskStatus::Pending),
+            "open" => Some(TaskStatus::Open),
+            "finished" => Some(TaskStatus::Finished),
+            "rejected" => Some(TaskStatus::Rejected),
+            "expired" => Some(TaskStatus::Expired),
+            "cancelled" => Some(TaskStatus::Cancelled),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -186,4 +527,8 @@ pub enum TaskDomainError {
     TaskDoesNotExpire,
     #[error("not authorized for action")]
     NotAuthorized,
+    #[error("task does not exist")]
+    TaskDoesNotExist,
+    #[error("task was already claimed")]
+    AlreadyClaimed,
 }
@@ -0,0 +1,99 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::shared::account::AccountId;
+
+use super::task::TaskId;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Hash, Eq, Default, Deserialize, Serialize)]
+pub struct TradeId(pub Ulid);
+
+impl TradeId {
+    pub fn new() -> TradeId {
+        TradeId(Ulid::new())
+    }
+
+    pub fn ulid(&self) -> Ulid {
+        self.0
+    }
+}
+
+//a handoff of one task, or a swap when the counterparty gives a task back
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trade {
+    pub id: TradeId,
+    pub proposed_by: AccountId,
+    pub counterparty: AccountId,
+    pub offered: TaskId,
+    pub requested: Option<TaskId>,
+    pub accepted: bool,
+    //whoever assigned the traded tasks, only asked when the organization wants trades approved
+    pub approvers: BTreeSet<AccountId>,
+    pub approved_by: BTreeSet<AccountId>,
+}
+
+impl Trade {
+    pub fn involves(&self, task: TaskId) -> bool {
+        self.offered == task || self.requested == Some(task)
+    }
+
+    //parties to the trade do not have to approve what they proposed or accepted themselves
+    pub fn approved(&self) -> bool {
+        self.approvers.iter().all(|approver| {
+            self.approved_by.contains(approver)
+                || *approver == self.proposed_by
+                || (self.accepted && *approver == self.counterparty)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(approvers: &[AccountId]) -> Trade {
+        Trade {
+            id: TradeId::new(),
+            proposed_by: AccountId::new(),
+            counterparty: AccountId::new(),
+            offered: TaskId::new(),
+            requested: None,
+            accepted: false,
+            approvers: approvers.iter().copied().collect(),
+            approved_by: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn every_approver_has_to_approve() {
+        assert!(trade(&[]).approved());
+        let approvers = [AccountId::new(), AccountId::new()];
+        let mut trade = trade(&approvers);
+        assert!(!trade.approved());
+        trade.approved_by.insert(approvers[0]);
+        assert!(!trade.approved());
+        trade.approved_by.insert(approvers[1]);
+        assert!(trade.approved());
+    }
+
+    #[test]
+    fn parties_approve_by_taking_part() {
+        let mut trade = trade(&[]);
+        trade.approvers = BTreeSet::from([trade.proposed_by, trade.counterparty]);
+        assert!(!trade.approved());
+        trade.accepted = true;
+        assert!(trade.approved());
+    }
+
+    #[test]
+    fn involves_both_tasks() {
+        let mut trade = trade(&[]);
+        let requested = TaskId::new();
+        assert!(!trade.involves(requested));
+        trade.requested = Some(requested);
+        assert!(trade.involves(requested));
+        assert!(trade.involves(trade.offered));
+    }
+}
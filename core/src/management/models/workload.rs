@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::shared::account::AccountId;
+
+use super::organization::TagId;
+
+//limits on how much one account gets, None leaves that side open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct WorkloadCaps {
+    pub tasks_per_day: Option<u32>,
+    pub tasks_per_week: Option<u32>,
+    pub effort_per_week: Option<u32>,
+}
+
+impl WorkloadCaps {
+    //the tighter of both limits on every side
+    pub fn min(self, other: WorkloadCaps) -> WorkloadCaps {
+        fn tighter(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+        WorkloadCaps {
+            tasks_per_day: tighter(self.tasks_per_day, other.tasks_per_day),
+            tasks_per_week: tighter(self.tasks_per_week, other.tasks_per_week),
+            effort_per_week: tighter(self.effort_per_week, other.effort_per_week),
+        }
+    }
+}
+
+//caps are set for one account, or for every worker of a tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum CapTarget {
+    Account(AccountId),
+    Tag(TagId),
+}
+
+//the local day a task counts on and the first day of that day's week
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DueDay {
+    pub day: NaiveDate,
+    pub week: NaiveDate,
+}
+
+//tasks and their effort due on one day, or summed over several
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Default)]
+pub struct DayLoad {
+    pub tasks: u32,
+    pub effort: u32,
+}
+
+//what an account has due by local day, attached by repositories like effort
+//a task counts on the day it is due, or on the day it was given when it has no deadline
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Default)]
+pub struct Workload {
+    pub days: BTreeMap<NaiveDate, DayLoad>,
+}
+
+impl Workload {
+    pub fn on(&self, day: NaiveDate) -> DayLoad {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    //everything due in the seven days from the week's first day
+    pub fn week(&self, start: NaiveDate) -> DayLoad {
+        let end = start
+            .checked_add_days(Days::new(7))
+            .unwrap_or(NaiveDate::MAX);
+        self.days
+            .range(start..end)
+            .fold(DayLoad::default(), |sum, (_, load)| DayLoad {
+                tasks: sum.tasks.saturating_add(load.tasks),
+                effort: sum.effort.saturating_add(load.effort),
+            })
+    }
+
+    //whether one more task of the effort due on the day stays within the caps
+    pub fn fits(&self, caps: &WorkloadCaps, effort: u32, due: DueDay) -> bool {
+        let on_day = self.on(due.day);
+        let in_week = self.week(due.week);
+        caps.tasks_per_day.is_none_or(|cap| on_day.tasks < cap)
+            && caps.tasks_per_week.is_none_or(|cap| in_week.tasks < cap)
+            && caps
+                .effort_per_week
+                .is_none_or(|cap| in_week.effort.saturating_add(effort) <= cap)
+    }
+
+    pub fn add(&mut self, due: DueDay, effort: u32) {
+        let load = self.days.entry(due.day).or_default();
+        load.tasks = load.tasks.saturating_add(1);
+        load.effort = load.effort.saturating_add(effort);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    //2024-01-01 is a monday
+    fn due(day: u32) -> DueDay {
+        DueDay {
+            day: date(day),
+            week: date(1 + (day - 1) / 7 * 7),
+        }
+    }
+
+    #[test]
+    fn fits_without_caps() {
+        let mut workload = Workload::default();
+        for _ in 0..20 {
+            workload.add(due(3), 5);
+        }
+        assert!(workload.fits(&WorkloadCaps::default(), 100, due(3)));
+    }
+
+    #[test]
+    fn tasks_per_day_only_counts_the_due_day() {
+        let caps = WorkloadCaps {
+            tasks_per_day: Some(2),
+            ..Default::default()
+        };
+        let mut workload = Workload::default();
+        workload.add(due(3), 1);
+        assert!(workload.fits(&caps, 1, due(3)));
+        workload.add(due(3), 1);
+        assert!(!workload.fits(&caps, 1, due(3)));
+        assert!(workload.fits(&caps, 1, due(4)));
+    }
+
+    #[test]
+    fn week_caps_sum_the_days_of_the_week() {
+        let caps = WorkloadCaps {
+            tasks_per_week: Some(3),
+            effort_per_week: Some(10),
+            ..Default::default()
+        };
+        let mut workload = Workload::default();
+        workload.add(due(1), 4);
+        workload.add(due(7), 4);
+        assert_eq!(
+            workload.week(date(1)),
+            DayLoad {
+                tasks: 2,
+                effort: 8
+            }
+        );
+        assert!(workload.fits(&caps, 2, due(5)));
+        assert!(!workload.fits(&caps, 3, due(5)));
+        //the next week starts empty
+        assert!(workload.fits(&caps, 10, due(8)));
+        workload.add(due(3), 1);
+        assert!(!workload.fits(&caps, 1, due(5)));
+    }
+
+    #[test]
+    fn caps_are_the_tighter_of_both() {
+        let account = WorkloadCaps {
+            tasks_per_day: Some(3),
+            tasks_per_week: None,
+            effort_per_week: Some(10),
+        };
+        let tag = WorkloadCaps {
+            tasks_per_day: Some(1),
+            tasks_per_week: Some(5),
+            effort_per_week: None,
+        };
+        assert_eq!(
+            account.min(tag),
+            WorkloadCaps {
+                tasks_per_day: Some(1),
+                tasks_per_week: Some(5),
+                effort_per_week: Some(10),
+            }
+        );
+    }
+}
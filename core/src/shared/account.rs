@@ -9,7 +9,7 @@ pub struct AccountId(Ulid);
 
 impl AccountId {
     pub fn new() -> Self {
-        Self(Ulid::new()) 
+        Self(Ulid::new())
     }
 
     pub fn ulid(&self) -> Ulid {
@@ -23,6 +23,12 @@ impl From<Uuid> for AccountId {
     }
 }
 
+impl From<Ulid> for AccountId {
+    fn from(value: Ulid) -> Self {
+        Self(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Account {
     id: AccountId,
@@ -1 +1,2 @@
-pub mod account;
\ No newline at end of file
+pub mod account;
+pub mod serde_duration;
@@ -0,0 +1,33 @@
+CREATE TABLE
+    IF NOT EXISTS ORGANIZATION_EVENT (
+        sequence bigserial PRIMARY KEY,
+        organization uuid NOT NULL,
+        payload jsonb NOT NULL,
+        created timestamptz NOT NULL DEFAULT now()
+    );
+
+CREATE INDEX IF NOT EXISTS ORGANIZATION_EVENT_ORGANIZATION ON ORGANIZATION_EVENT (organization);
+
+CREATE TABLE
+    IF NOT EXISTS TASK_EVENT (
+        sequence bigserial PRIMARY KEY,
+        task uuid NOT NULL,
+        organization uuid NOT NULL,
+        payload jsonb NOT NULL,
+        created timestamptz NOT NULL DEFAULT now()
+    );
+
+CREATE INDEX IF NOT EXISTS TASK_EVENT_TASK ON TASK_EVENT (task);
+
+CREATE INDEX IF NOT EXISTS TASK_EVENT_ORGANIZATION ON TASK_EVENT (organization);
+
+CREATE TABLE
+    IF NOT EXISTS TASK_INSTANCE (
+        id uuid PRIMARY KEY,
+        organization uuid NOT NULL,
+        catalogue_task uuid NOT NULL,
+        assigned_to uuid NOT NULL,
+        assigned_by uuid NOT NULL,
+        expires timestamptz,
+        status varchar(16) NOT NULL
+    );
@@ -0,0 +1,6 @@
+CREATE TABLE
+    IF NOT EXISTS ACCOUNT (
+        id uuid PRIMARY KEY,
+        username varchar(80) NOT NULL UNIQUE,
+        password text NOT NULL
+    );
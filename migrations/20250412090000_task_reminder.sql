@@ -0,0 +1,7 @@
+CREATE TABLE
+    IF NOT EXISTS TASK_REMINDER (
+        task uuid NOT NULL,
+        threshold_seconds bigint NOT NULL,
+        sent timestamptz NOT NULL DEFAULT now(),
+        PRIMARY KEY (task, threshold_seconds)
+    );
@@ -0,0 +1,4 @@
+ALTER TABLE TASK_INSTANCE
+ADD COLUMN IF NOT EXISTS tags uuid[] NOT NULL DEFAULT '{}',
+ADD COLUMN IF NOT EXISTS reassigned_from uuid,
+ADD COLUMN IF NOT EXISTS reassignments integer NOT NULL DEFAULT 0;
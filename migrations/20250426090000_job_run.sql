@@ -0,0 +1,16 @@
+CREATE TABLE
+    IF NOT EXISTS JOB_RUN (
+        id bigserial PRIMARY KEY,
+        job varchar(32) NOT NULL,
+        window_start timestamptz NOT NULL,
+        started timestamptz NOT NULL DEFAULT now(),
+        finished timestamptz,
+        outcome varchar(16) NOT NULL,
+        processed bigint NOT NULL DEFAULT 0,
+        failed bigint NOT NULL DEFAULT 0,
+        message text
+    );
+
+CREATE UNIQUE INDEX IF NOT EXISTS JOB_RUN_SUCCEEDED ON JOB_RUN (job, window_start)
+WHERE
+    outcome = 'succeeded';
@@ -0,0 +1,20 @@
+CREATE TABLE
+    IF NOT EXISTS WEEKLY_STATS (
+        organization uuid NOT NULL,
+        account uuid NOT NULL,
+        week_start date NOT NULL,
+        iso_year integer NOT NULL,
+        iso_week integer NOT NULL,
+        assigned integer NOT NULL,
+        finished integer NOT NULL,
+        rejected integer NOT NULL,
+        expired integer NOT NULL,
+        PRIMARY KEY (organization, account, week_start)
+    );
+
+-- weeks that have been aggregated, weeks without any activity have no WEEKLY_STATS rows
+CREATE TABLE
+    IF NOT EXISTS STATS_WEEK (
+        week_start date PRIMARY KEY,
+        computed timestamptz NOT NULL DEFAULT now()
+    );
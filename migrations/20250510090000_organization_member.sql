@@ -0,0 +1,10 @@
+-- read model of who belongs to which organization, rebuilt from ORGANIZATION_EVENT on every write
+CREATE TABLE
+    IF NOT EXISTS ORGANIZATION_MEMBER (
+        organization uuid NOT NULL,
+        account uuid NOT NULL,
+        account_type varchar(16) NOT NULL,
+        PRIMARY KEY (organization, account)
+    );
+
+CREATE INDEX IF NOT EXISTS ORGANIZATION_MEMBER_ACCOUNT ON ORGANIZATION_MEMBER (account);
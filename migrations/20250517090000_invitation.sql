@@ -0,0 +1,15 @@
+CREATE TABLE
+    IF NOT EXISTS INVITATION (
+        id uuid PRIMARY KEY,
+        organization uuid NOT NULL,
+        code varchar(32) NOT NULL UNIQUE,
+        account_type varchar(16) NOT NULL,
+        max_uses integer,
+        uses integer NOT NULL,
+        expires timestamptz,
+        revoked boolean NOT NULL,
+        -- two redemptions racing for the last use cannot both be projected
+        CHECK (max_uses IS NULL OR uses <= max_uses)
+    );
+
+CREATE INDEX IF NOT EXISTS INVITATION_ORGANIZATION ON INVITATION (organization);
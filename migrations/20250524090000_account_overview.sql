@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS TASK_INSTANCE_PENDING_ASSIGNED_TO ON TASK_INSTANCE (assigned_to)
+WHERE
+    status = 'pending';
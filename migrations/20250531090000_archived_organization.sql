@@ -0,0 +1,6 @@
+-- read model of archived organizations, rebuilt from ORGANIZATION_EVENT on every write
+CREATE TABLE
+    IF NOT EXISTS ARCHIVED_ORGANIZATION (
+        organization uuid PRIMARY KEY,
+        archived timestamptz NOT NULL
+    );
@@ -0,0 +1,2 @@
+ALTER TABLE CATALOGUE_TASK
+ADD COLUMN IF NOT EXISTS effort integer NOT NULL DEFAULT 1 CHECK (effort >= 0);
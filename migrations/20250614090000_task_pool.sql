@@ -0,0 +1,7 @@
+ALTER TABLE TASK_INSTANCE
+ADD COLUMN IF NOT EXISTS pooled boolean NOT NULL DEFAULT false,
+ADD COLUMN IF NOT EXISTS claim_deadline timestamptz,
+ADD COLUMN IF NOT EXISTS fallback jsonb;
+
+CREATE INDEX IF NOT EXISTS TASK_INSTANCE_OPEN ON TASK_INSTANCE (organization)
+WHERE status = 'open';
@@ -0,0 +1,3 @@
+ALTER TABLE CATALOGUE_TASK
+ADD COLUMN IF NOT EXISTS min_age integer CHECK (min_age >= 0),
+ADD COLUMN IF NOT EXISTS skills text[] NOT NULL DEFAULT '{}';
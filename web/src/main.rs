@@ -1,3 +1,107 @@
-fn main() {
-    println!("Hello, world!");
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use chores::{
+    management::{
+        application::notifications::Notification,
+        infrastructure::{PostgressOrganizationRepository, PostgressTaskRepository},
+        models::{organization::OrganizationError, task::TaskDomainError},
+    },
+    shared::account::AccountId,
+    AccountOverview, ManagementService, Notifier,
+};
+
+//delivery channels are not wired up yet, notifications end up in the server log
+#[derive(Debug, Clone)]
+struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    async fn notify(
+        &self,
+        account: AccountId,
+        notification: Notification,
+    ) -> Result<(), anyhow::Error> {
+        println!("notify {}: {}", account.ulid(), notification);
+        Ok(())
+    }
+}
+
+type Management =
+    ManagementService<PostgressTaskRepository, PostgressOrganizationRepository, StdoutNotifier>;
+
+struct AppError(anyhow::Error);
+
+impl<E: Into<anyhow::Error>> From<E> for AppError {
+    fn from(value: E) -> Self {
+        Self(value.into())
+    }
+}
+
+impl AppError {
+    fn status(&self) -> StatusCode {
+        if let Some(error) = self.0.downcast_ref::<OrganizationError>() {
+            return match error {
+                OrganizationError::OrganizationDoesNotExist
+                | OrganizationError::TagDoesNotExist
+                | OrganizationError::InvitationDoesNotExist
+                | OrganizationError::UnavailabilityDoesNotExist
+                | OrganizationError::TradeDoesNotExist
+                | OrganizationError::TaskError(TaskDomainError::TaskDoesNotExist) => {
+                    StatusCode::NOT_FOUND
+                }
+                OrganizationError::NotAuthorized
+                | OrganizationError::NotInOrg
+                | OrganizationError::TaskError(TaskDomainError::NotAuthorized) => {
+                    StatusCode::FORBIDDEN
+                }
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+        }
+        match self.0.downcast_ref::<TaskDomainError>() {
+            Some(TaskDomainError::TaskDoesNotExist) => StatusCode::NOT_FOUND,
+            Some(TaskDomainError::NotAuthorized) => StatusCode::FORBIDDEN,
+            Some(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            None => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status == StatusCode::INTERNAL_SERVER_ERROR {
+            eprintln!("request failed: {:#}", self.0);
+            return (status, "internal server error").into_response();
+        }
+        (status, self.0.to_string()).into_response()
+    }
+}
+
+//not routed until requests are authenticated, anyone could read any account's overview by its id
+#[allow(dead_code)]
+async fn account_overview(
+    State(service): State<Management>,
+    Path(account): Path<AccountId>,
+) -> Result<Json<AccountOverview>, AppError> {
+    Ok(Json(service.account_overview(account).await?))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let database_url = std::env::var("DATABASE_URL")?;
+    let address = std::env::var("BIND_ADDRESS").unwrap_or("0.0.0.0:3000".to_string());
+
+    let service = ManagementService::new(
+        PostgressTaskRepository::new(&database_url).await?,
+        PostgressOrganizationRepository::new(&database_url).await?,
+        StdoutNotifier,
+    );
+    let app = Router::new().with_state(service);
+
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
 }